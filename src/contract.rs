@@ -1,26 +1,45 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Uint128,
+    to_binary, to_vec, Addr, Binary, BlockInfo, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw4::MemberResponse;
 use cw4_group::msg::QueryMsg as Cw4QueryMsg;
 use cw_storage_plus::Bound;
-use cw_utils::maybe_addr;
+use cw_utils::{maybe_addr, Expiration, Scheduled};
+use semver::Version;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
+use crate::migrations::run_migrations;
 use crate::msg::{
-    EntriesResponse, ExecuteMsg, InstantiateMsg, QueryMsg, TallyVotesResponse, VotesResponse,
+    Ballot, EntriesResponse, EntryScoresResponse, EventResponse, ExecuteMsg, InstantiateMsg,
+    LeaderboardResponse, MigrateMsg, QueryMsg, ResultsResponse, TallyVotesResponse, VotesResponse,
 };
 use crate::state::{
-    Config, Entry, Votes, CATEGORIES, CATEGORY_ENTRIES, CONFIG, ENTRY_ID, ENTRY_VOTES,
+    median, CategoryInfo, CategoryResults, Config, Entry, EntryTotals, EventInfo, MakersSnapshot,
+    Normalization, NormalizationStage, Phase, PhaseInfo, Placement, RankBy, ScoreStats,
+    ScoringMode, VoteRecord, Votes, VotingWindow, BALLOT_WEIGHTS, CATEGORIES, CATEGORY_ENTRIES,
+    CATEGORY_INFO, COMMITMENTS, CONFIG, DEFAULT_CRITERIA, ENTRY_CATEGORY, ENTRY_ID, ENTRY_MEDIANS,
+    ENTRY_SUBMITTED, ENTRY_TOTALS, ENTRY_VOTES, EVENTS, EVENT_ID, GLOBAL_STATS, JUDGE_STATS,
+    MAKERS_SNAPSHOT, NORMALIZATION, NORMALIZED_TOTALS, PHASE, RESULTS, SORTED_SCORES, VOTE_HISTORY,
+    VOTING_WINDOW,
 };
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:voting-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Scores are fixed point numbers with two decimals, 1.00 to 10.00 by default
+pub(crate) const DEFAULT_MIN_SCORE: u128 = 100;
+pub(crate) const DEFAULT_MAX_SCORE: u128 = 1000;
+pub(crate) const DEFAULT_MIN_BALLOTS: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -33,13 +52,26 @@ pub fn instantiate(
     let admins_cw4_group = deps.api.addr_validate(&msg.admins_cw4_group)?;
     let makers_cw4_group = deps.api.addr_validate(&msg.makers_cw4_group)?;
 
+    let min_score = msg.min_score.unwrap_or(Uint128::new(DEFAULT_MIN_SCORE));
+    let max_score = msg.max_score.unwrap_or(Uint128::new(DEFAULT_MAX_SCORE));
+    if min_score > max_score || msg.score_step == Some(Uint128::zero()) {
+        return Err(ContractError::InvalidScoreRange {});
+    };
+
     let config = Config {
         admins_cw4_group,
         makers_cw4_group,
+        min_score,
+        max_score,
+        score_step: msg.score_step,
+        commit_reveal: msg.commit_reveal.unwrap_or(false),
+        min_ballots: msg.min_ballots.unwrap_or(DEFAULT_MIN_BALLOTS),
+        max_amendments: msg.max_amendments,
+        equal_weights: msg.equal_weights.unwrap_or(false),
     };
     CONFIG.save(deps.storage, &config)?;
 
-    CATEGORIES.save(deps.storage, &vec![])?;
+    EVENT_ID.save(deps.storage, &0)?;
 
     ENTRY_ID.save(deps.storage, &0)?;
 
@@ -54,8 +86,33 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::AddCategory { category } => execute_add_category(deps, env, info, category),
+        ExecuteMsg::CreateEvent {
+            name,
+            date,
+            location,
+        } => execute_create_event(deps, env, info, name, date, location),
+        ExecuteMsg::AddCategory {
+            event,
+            category,
+            criteria,
+            weights,
+            min_ballots,
+            scoring,
+            tie_breakers,
+        } => execute_add_category(
+            deps,
+            env,
+            info,
+            event,
+            category,
+            criteria,
+            weights,
+            min_ballots,
+            scoring,
+            tie_breakers,
+        ),
         ExecuteMsg::AddEntry {
+            event,
             name,
             category,
             maker_addr,
@@ -64,26 +121,121 @@ pub fn execute(
             genetics,
             farmer,
         } => execute_add_entry(
-            deps, env, info, name, category, maker_addr, maker_name, breeder, genetics, farmer,
+            deps, env, info, event, name, category, maker_addr, maker_name, breeder, genetics,
+            farmer,
         ),
         ExecuteMsg::Vote {
+            event,
+            category,
+            entry_id,
+            votes,
+        } => execute_vote(deps, env, info, event, category, entry_id, votes),
+        ExecuteMsg::VoteBatch { event, ballots } => {
+            execute_vote_batch(deps, env, info, event, ballots)
+        }
+        ExecuteMsg::CommitVote {
+            event,
+            category,
+            entry_id,
+            commitment,
+        } => execute_commit_vote(deps, env, info, event, category, entry_id, commitment),
+        ExecuteMsg::RevealVote {
+            event,
             category,
             entry_id,
             votes,
-        } => execute_vote(deps, env, info, category, entry_id, votes),
+            salt,
+        } => execute_reveal_vote(deps, env, info, event, category, entry_id, votes, salt),
+        ExecuteMsg::RetractVote { event, entry_id } => {
+            execute_retract_vote(deps, env, info, event, entry_id)
+        }
+        ExecuteMsg::SetPhase { event, phase } => execute_set_phase(deps, env, info, event, phase),
+        ExecuteMsg::UpdateConfig {
+            admins_cw4_group,
+            makers_cw4_group,
+        } => execute_update_config(deps, env, info, admins_cw4_group, makers_cw4_group),
+        ExecuteMsg::SetVotingWindow {
+            event,
+            category,
+            opens,
+            closes,
+        } => execute_set_voting_window(deps, env, info, event, category, opens, closes),
+        ExecuteMsg::ComputeMedians {
+            event,
+            category,
+            start_after,
+            limit,
+        } => execute_compute_medians(deps, env, info, event, category, start_after, limit),
+        ExecuteMsg::Normalize { event, limit } => execute_normalize(deps, env, info, event, limit),
+        ExecuteMsg::Finalize {
+            event,
+            category,
+            rank_by,
+        } => execute_finalize(deps, env, info, event, category, rank_by),
     }
 }
 
+fn execute_create_event(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    date: Option<String>,
+    location: Option<String>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    let event_id = (EVENT_ID.load(deps.storage)?) + 1;
+
+    let event_info = EventInfo {
+        name,
+        date,
+        location,
+    };
+    EVENTS.save(deps.storage, event_id, &event_info)?;
+
+    CATEGORIES.save(deps.storage, event_id, &vec![])?;
+
+    VOTING_WINDOW.save(deps.storage, event_id, &VotingWindow::default())?;
+
+    PHASE.save(
+        deps.storage,
+        event_id,
+        &PhaseInfo {
+            phase: Phase::Registration,
+            changed_at_height: env.block.height,
+            changed_at_time: env.block.time,
+        },
+    )?;
+
+    EVENT_ID.save(deps.storage, &event_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_event")
+        .add_attribute("event_id", event_id.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_add_category(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
+    event: u64,
     category: String,
+    criteria: Option<Vec<String>>,
+    weights: Option<BTreeMap<String, Decimal>>,
+    min_ballots: Option<u64>,
+    scoring: Option<ScoringMode>,
+    tie_breakers: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
     // Check if the sender is in admin cw4 group
     check_admin_membership(&deps, &info.sender)?;
 
-    let mut categories = CATEGORIES.load(deps.storage)?;
+    // Categories can only be added while registration is open
+    check_phase(&deps, event, Phase::Registration)?;
+
+    let mut categories = CATEGORIES.load(deps.storage, event)?;
 
     // Check if the category already exists
     if categories.contains(&category) {
@@ -91,15 +243,56 @@ fn execute_add_category(
     }
     categories.push(category.clone());
 
-    CATEGORIES.save(deps.storage, &categories)?;
+    CATEGORIES.save(deps.storage, event, &categories)?;
+
+    let criteria =
+        criteria.unwrap_or_else(|| DEFAULT_CRITERIA.iter().map(|c| c.to_string()).collect());
+    validate_criteria(&criteria)?;
+
+    // Every criterion is weighted 1 unless a weight was given for it
+    let weights = weights.unwrap_or_default();
+    if let Some(criterion) = weights.keys().find(|c| !criteria.contains(c)) {
+        return Err(ContractError::UnknownCriterion {
+            criterion: criterion.clone(),
+        });
+    };
+    let weights = criteria
+        .iter()
+        .map(|c| (c.clone(), weights.get(c).copied().unwrap_or(Decimal::one())))
+        .collect();
+
+    // Tie-breakers must be distinct criteria of the category
+    let tie_breakers = tie_breakers.unwrap_or_default();
+    if let Some(criterion) = tie_breakers.iter().find(|c| !criteria.contains(c)) {
+        return Err(ContractError::UnknownCriterion {
+            criterion: criterion.clone(),
+        });
+    };
+    if tie_breakers.iter().collect::<BTreeSet<_>>().len() != tie_breakers.len() {
+        return Err(ContractError::InvalidCriteria {});
+    };
+
+    let category_info = CategoryInfo {
+        event,
+        name: category.clone(),
+        criteria,
+        weights,
+        voting_window: VotingWindow::default(),
+        min_ballots,
+        scoring: scoring.unwrap_or_default(),
+        tie_breakers,
+    };
+    CATEGORY_INFO.save(deps.storage, (event, category), &category_info)?;
 
     Ok(Response::new().add_attribute("action", "add_category"))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_add_entry(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
+    event: u64,
     name: String,
     category: String,
     maker_addr: String,
@@ -111,7 +304,10 @@ fn execute_add_entry(
     // Check if the sender is in admin cw4 group
     check_admin_membership(&deps, &info.sender)?;
 
-    let categories = CATEGORIES.load(deps.storage)?;
+    // Entries can only be added while registration is open
+    check_phase(&deps, event, Phase::Registration)?;
+
+    let categories = CATEGORIES.load(deps.storage, event)?;
     if !categories.contains(&category) {
         return Err(ContractError::InvalidCategory {});
     };
@@ -130,179 +326,1676 @@ fn execute_add_entry(
         farmer,
     };
 
-    CATEGORY_ENTRIES.save(deps.storage, (category, entry_id), &entry)?;
+    CATEGORY_ENTRIES.save(deps.storage, (event, category.clone(), entry_id), &entry)?;
+    ENTRY_CATEGORY.save(deps.storage, entry_id, &(event, category))?;
+    ENTRY_SUBMITTED.save(deps.storage, entry_id, &env.block.height)?;
     ENTRY_ID.save(deps.storage, &entry_id)?;
 
     Ok(Response::new().add_attribute("action", "add_entry"))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn execute_vote(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    category: String,
-    entry_id: u8,
+    event: u64,
+    category: Option<String>,
+    entry_id: u64,
     votes: Votes,
 ) -> Result<Response, ContractError> {
     // Check if the sender is in makers cw4 group
-    check_maker_membership(&deps, &info.sender)?;
+    let weight = check_maker_membership(&deps, event, &info.sender)?;
 
-    let categories = CATEGORIES.load(deps.storage)?;
-    // Check if the category is valid
-    if !categories.contains(&category) {
-        return Err(ContractError::InvalidCategory {});
+    // Votes are only accepted during the voting phase
+    check_phase(&deps, event, Phase::Voting)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if config.commit_reveal {
+        return Err(ContractError::CommitRevealEnabled {});
     };
 
-    let entry = CATEGORY_ENTRIES.load(deps.storage, (category.clone(), entry_id))?;
+    let category_info = load_ballot_category(&deps, &info.sender, event, category, entry_id)?;
 
-    // Check if the sender is not the same as the entry maker
-    if info.sender == entry.maker_addr {
-        return Err(ContractError::InvalidMaker {});
-    };
+    // Check if voting is open for both the event and the category
+    check_voting_windows(&deps, &env.block, &category_info)?;
+
+    // Check if the ballot scores exactly the category criteria within the configured range
+    validate_votes(&config, &category_info.criteria, &votes)?;
 
-    ENTRY_VOTES.save(deps.storage, (entry_id, info.sender), &votes)?;
+    save_votes(
+        deps.storage,
+        &config,
+        &category_info,
+        &env.block,
+        entry_id,
+        &info.sender,
+        &votes,
+        weight,
+    )?;
 
     Ok(Response::new().add_attribute("action", "vote"))
 }
 
-fn check_admin_membership(deps: &DepsMut, sender: &Addr) -> Result<(), ContractError> {
+fn execute_vote_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    ballots: Vec<Ballot>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in makers cw4 group, once for the whole batch
+    let weight = check_maker_membership(&deps, event, &info.sender)?;
+
+    // Votes are only accepted during the voting phase
+    check_phase(&deps, event, Phase::Voting)?;
+
     let config = CONFIG.load(deps.storage)?;
+    if config.commit_reveal {
+        return Err(ContractError::CommitRevealEnabled {});
+    };
 
-    // Check for admin cw4 group membership
-    let res: MemberResponse = deps.querier.query_wasm_smart(
-        config.admins_cw4_group,
-        &Cw4QueryMsg::Member {
-            addr: sender.to_string(),
-            at_height: None,
-        },
-    )?;
-    if res.weight.is_none() {
-        return Err(ContractError::Unauthorized {});
+    if ballots.is_empty() {
+        return Err(ContractError::EmptyBatch {});
     };
 
-    Ok(())
+    // Every ballot is checked before any is saved, so the batch is saved whole or not at all
+    let mut entry_ids = BTreeSet::new();
+    let mut checked = Vec::with_capacity(ballots.len());
+    for (index, ballot) in ballots.into_iter().enumerate() {
+        let category_info = check_batch_ballot(
+            &deps,
+            &env.block,
+            &config,
+            &info.sender,
+            event,
+            &ballot,
+            &mut entry_ids,
+        )
+        .map_err(|err| ContractError::InvalidBallot {
+            index,
+            entry_id: ballot.entry_id,
+            source: Box::new(err),
+        })?;
+        checked.push((category_info, ballot));
+    }
+
+    for (category_info, ballot) in &checked {
+        save_votes(
+            deps.storage,
+            &config,
+            category_info,
+            &env.block,
+            ballot.entry_id,
+            &info.sender,
+            &ballot.votes,
+            weight,
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "vote_batch")
+        .add_attribute("count", checked.len().to_string()))
+}
+
+/// Checks a ballot of a batch like a single vote and returns the category of its entry
+fn check_batch_ballot(
+    deps: &DepsMut,
+    block: &BlockInfo,
+    config: &Config,
+    sender: &Addr,
+    event: u64,
+    ballot: &Ballot,
+    entry_ids: &mut BTreeSet<u64>,
+) -> Result<CategoryInfo, ContractError> {
+    // Check if the entry is not scored twice in the batch
+    if !entry_ids.insert(ballot.entry_id) {
+        return Err(ContractError::DuplicateEntry {});
+    };
+
+    let category_info = load_ballot_category(deps, sender, event, None, ballot.entry_id)?;
+
+    // Check if voting is open for both the event and the category
+    check_voting_windows(deps, block, &category_info)?;
+
+    // Check if the ballot scores exactly the category criteria within the configured range
+    validate_votes(config, &category_info.criteria, &ballot.votes)?;
+
+    // Check if the ballot is not an amendment beyond the limit
+    next_sequence(deps.storage, config, ballot.entry_id, sender)?;
+
+    Ok(category_info)
 }
 
-fn check_maker_membership(deps: &DepsMut, sender: &Addr) -> Result<(), ContractError> {
+#[allow(clippy::too_many_arguments)]
+fn execute_commit_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    category: Option<String>,
+    entry_id: u64,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in makers cw4 group
+    check_maker_membership(&deps, event, &info.sender)?;
+
+    // Commitments are only accepted during the voting phase
+    check_phase(&deps, event, Phase::Voting)?;
+
     let config = CONFIG.load(deps.storage)?;
+    if !config.commit_reveal {
+        return Err(ContractError::CommitRevealDisabled {});
+    };
 
-    // Check for admin cw4 group membership
-    let res: MemberResponse = deps.querier.query_wasm_smart(
-        config.makers_cw4_group,
-        &Cw4QueryMsg::Member {
-            addr: sender.to_string(),
-            at_height: None,
-        },
+    let category_info = load_ballot_category(&deps, &info.sender, event, category, entry_id)?;
+
+    // Check if voting is open for both the event and the category
+    check_voting_windows(&deps, &env.block, &category_info)?;
+
+    COMMITMENTS.save(deps.storage, (entry_id, info.sender), &commitment)?;
+
+    Ok(Response::new().add_attribute("action", "commit_vote"))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_reveal_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    category: Option<String>,
+    entry_id: u64,
+    votes: Votes,
+    salt: String,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in makers cw4 group, revealed ballots weigh as much as the
+    // judge did when voting opened
+    let weight = check_maker_membership(&deps, event, &info.sender)?;
+
+    // Commitments are only revealed once voting has ended
+    check_phase(&deps, event, Phase::Reveal)?;
+
+    let category_info = load_ballot_category(&deps, &info.sender, event, category, entry_id)?;
+
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, (entry_id, info.sender.clone()))?
+        .ok_or(ContractError::NoCommitment {})?;
+
+    // Check if the revealed votes and salt hash to the commitment
+    let preimage = [to_vec(&votes)?, salt.into_bytes()].concat();
+    if Sha256::digest(preimage).as_slice() != commitment.as_slice() {
+        return Err(ContractError::CommitmentMismatch {});
+    };
+
+    // Check if the ballot scores exactly the category criteria within the configured range
+    let config = CONFIG.load(deps.storage)?;
+    validate_votes(&config, &category_info.criteria, &votes)?;
+
+    COMMITMENTS.remove(deps.storage, (entry_id, info.sender.clone()));
+    save_votes(
+        deps.storage,
+        &config,
+        &category_info,
+        &env.block,
+        entry_id,
+        &info.sender,
+        &votes,
+        weight,
     )?;
-    if res.weight.is_none() {
-        return Err(ContractError::Unauthorized {});
+
+    Ok(Response::new().add_attribute("action", "reveal_vote"))
+}
+
+fn execute_retract_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    entry_id: u64,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in makers cw4 group
+    check_maker_membership(&deps, event, &info.sender)?;
+
+    // Ballots can only be retracted while voting is open
+    check_phase(&deps, event, Phase::Voting)?;
+
+    let category_info = load_ballot_category(&deps, &info.sender, event, None, entry_id)?;
+
+    // Check if voting is open for both the event and the category
+    check_voting_windows(&deps, &env.block, &category_info)?;
+
+    // Committed ballots are not counted yet, so only the commitment is withdrawn
+    let config = CONFIG.load(deps.storage)?;
+    if config.commit_reveal {
+        if !COMMITMENTS.has(deps.storage, (entry_id, info.sender.clone())) {
+            return Err(ContractError::NoCommitment {});
+        };
+        COMMITMENTS.remove(deps.storage, (entry_id, info.sender.clone()));
+    } else {
+        let key = (event, entry_id, info.sender.clone());
+        let previous = ENTRY_VOTES
+            .may_load(deps.storage, key.clone())?
+            .ok_or(ContractError::NoVote {})?;
+
+        // A retraction is kept in the history as an empty ballot
+        record_ballot(
+            deps.storage,
+            &config,
+            &env.block,
+            entry_id,
+            &info.sender,
+            &Votes::new(),
+        )?;
+
+        let previous_weight = BALLOT_WEIGHTS.load(deps.storage, key.clone())?;
+        let mut totals = ENTRY_TOTALS.load(deps.storage, entry_id)?;
+        totals.remove(
+            &previous,
+            category_info.weighted_sum(&previous),
+            previous_weight,
+        );
+        remove_sorted_scores(
+            deps.storage,
+            &category_info,
+            entry_id,
+            &info.sender,
+            &previous,
+        );
+        ENTRY_TOTALS.save(deps.storage, entry_id, &totals)?;
+        ENTRY_VOTES.remove(deps.storage, key.clone());
+        BALLOT_WEIGHTS.remove(deps.storage, key);
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "retract_vote")
+        .add_attribute("entry_id", entry_id.to_string())
+        .add_attribute("maker_addr", info.sender))
+}
+
+/// Saves the votes of a voter with their weight, replacing any previous votes in the
+/// entry totals, and appends them to the history of the voter
+#[allow(clippy::too_many_arguments)]
+fn save_votes(
+    storage: &mut dyn Storage,
+    config: &Config,
+    category_info: &CategoryInfo,
+    block: &BlockInfo,
+    entry_id: u64,
+    voter: &Addr,
+    votes: &Votes,
+    weight: u64,
+) -> Result<(), ContractError> {
+    record_ballot(storage, config, block, entry_id, voter, votes)?;
+
+    let mut totals = ENTRY_TOTALS
+        .may_load(storage, entry_id)?
+        .unwrap_or_default();
+
+    let key = (category_info.event, entry_id, voter.clone());
+    if let Some(previous) = ENTRY_VOTES.may_load(storage, key.clone())? {
+        let previous_weight = BALLOT_WEIGHTS.load(storage, key.clone())?;
+        totals.remove(
+            &previous,
+            category_info.weighted_sum(&previous),
+            previous_weight,
+        );
+        remove_sorted_scores(storage, category_info, entry_id, voter, &previous);
     };
+    totals.add(votes, category_info.weighted_sum(votes), weight);
+    add_sorted_scores(storage, category_info, entry_id, voter, votes, weight)?;
+
+    ENTRY_TOTALS.save(storage, entry_id, &totals)?;
+    ENTRY_VOTES.save(storage, key.clone(), votes)?;
+    BALLOT_WEIGHTS.save(storage, key, &weight)?;
 
     Ok(())
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Categories {} => to_binary(&query_categories(deps)?),
-        QueryMsg::Entry { category, entry_id } => {
-            to_binary(&query_entry(deps, category, entry_id)?)
+/// Keeps the scores of a ballot in score order for categories scored by trimmed mean
+fn add_sorted_scores(
+    storage: &mut dyn Storage,
+    category_info: &CategoryInfo,
+    entry_id: u64,
+    voter: &Addr,
+    votes: &Votes,
+    weight: u64,
+) -> StdResult<()> {
+    if let ScoringMode::TrimmedMean { .. } = category_info.scoring {
+        for (criterion, score) in votes {
+            let key = ((entry_id, criterion.clone()), score.u128(), voter.clone());
+            SORTED_SCORES.save(storage, key, &weight)?;
         }
-        QueryMsg::Entries {
-            category,
-            start_after,
-            limit,
-        } => to_binary(&query_entries(deps, category, start_after, limit)?),
-        QueryMsg::TallyVotes {
-            entry_id,
-            start_after,
-            limit,
-        } => to_binary(&query_tally_votes(deps, entry_id, start_after, limit)?),
-        QueryMsg::Votes {
-            entry_id,
-            maker_addr,
-        } => to_binary(&query_votes(deps, entry_id, maker_addr)?),
-    }
+    };
+    Ok(())
 }
 
-fn query_categories(deps: Deps) -> StdResult<Vec<String>> {
-    let categories = CATEGORIES.load(deps.storage)?;
-    Ok(categories)
+fn remove_sorted_scores(
+    storage: &mut dyn Storage,
+    category_info: &CategoryInfo,
+    entry_id: u64,
+    voter: &Addr,
+    votes: &Votes,
+) {
+    if let ScoringMode::TrimmedMean { .. } = category_info.scoring {
+        for (criterion, score) in votes {
+            let key = ((entry_id, criterion.clone()), score.u128(), voter.clone());
+            SORTED_SCORES.remove(storage, key);
+        }
+    };
 }
 
-fn query_entry(deps: Deps, category: String, entry_id: u8) -> StdResult<Entry> {
-    let entry = CATEGORY_ENTRIES.load(deps.storage, (category, entry_id))?;
-    Ok(entry)
+/// Appends a ballot to the history of the voter, unless it would exceed the amendments
+/// allowed by the config
+fn record_ballot(
+    storage: &mut dyn Storage,
+    config: &Config,
+    block: &BlockInfo,
+    entry_id: u64,
+    voter: &Addr,
+    votes: &Votes,
+) -> Result<(), ContractError> {
+    let sequence = next_sequence(storage, config, entry_id, voter)?;
+    VOTE_HISTORY.save(
+        storage,
+        (entry_id, voter.clone(), sequence),
+        &VoteRecord {
+            votes: votes.clone(),
+            height: block.height,
+            time: block.time,
+        },
+    )?;
+
+    Ok(())
 }
 
-fn query_entries(
-    deps: Deps,
-    category: String,
-    start_after: Option<u8>,
-    limit: Option<u8>,
-) -> StdResult<Vec<EntriesResponse>> {
-    let limit = limit.unwrap_or(30) as usize;
-    let start = start_after.map(Bound::exclusive);
+/// Returns the sequence of the next ballot of the voter for the entry, if the config
+/// allows another amendment
+fn next_sequence(
+    storage: &dyn Storage,
+    config: &Config,
+    entry_id: u64,
+    voter: &Addr,
+) -> Result<u32, ContractError> {
+    // Every submission after the first one is an amendment
+    let sequence = VOTE_HISTORY
+        .prefix((entry_id, voter.clone()))
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |last| last + 1);
+    if let Some(max) = config.max_amendments {
+        if sequence > max {
+            return Err(ContractError::TooManyAmendments { max });
+        };
+    };
 
-    let entries = CATEGORY_ENTRIES
-        .prefix(category)
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            let (entry_id, entry) = item.unwrap();
-            EntriesResponse {
-                id: entry_id,
-                data: entry,
-            }
-        })
-        .collect::<Vec<EntriesResponse>>();
+    Ok(sequence)
+}
 
-    Ok(entries)
+/// Checks if the sender can vote on the entry and returns the category it belongs to
+fn load_ballot_category(
+    deps: &DepsMut,
+    sender: &Addr,
+    event: u64,
+    category: Option<String>,
+    entry_id: u64,
+) -> Result<CategoryInfo, ContractError> {
+    let (entry_event, entry_category) = ENTRY_CATEGORY.load(deps.storage, entry_id)?;
+    // Check if the entry belongs to the event
+    if entry_event != event {
+        return Err(ContractError::InvalidEvent {});
+    };
+
+    // Check if the category is the one of the entry, if given
+    if category.is_some_and(|category| category != entry_category) {
+        return Err(ContractError::InvalidCategory {});
+    };
+
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, entry_category.clone()))?;
+    let entry = CATEGORY_ENTRIES.load(deps.storage, (event, entry_category, entry_id))?;
+
+    // Check if the sender is not the same as the entry maker
+    if *sender == entry.maker_addr {
+        return Err(ContractError::InvalidMaker {});
+    };
+
+    Ok(category_info)
 }
 
-fn query_votes(deps: Deps, entry_id: u8, maker_addr: String) -> StdResult<Votes> {
-    let addr = deps.api.addr_validate(&maker_addr)?;
-    let votes = ENTRY_VOTES.load(deps.storage, (entry_id, addr))?;
-    Ok(votes)
+fn execute_set_phase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    phase: Phase,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    // Phases can only move forward one step at a time
+    let config = CONFIG.load(deps.storage)?;
+    let current = load_phase(deps.storage, event)?.phase;
+    if current.next(config.commit_reveal) != Some(phase) {
+        return Err(ContractError::InvalidPhaseTransition {
+            from: current,
+            to: phase,
+        });
+    };
+
+    PHASE.save(
+        deps.storage,
+        event,
+        &PhaseInfo {
+            phase,
+            changed_at_height: env.block.height,
+            changed_at_time: env.block.time,
+        },
+    )?;
+
+    // Judges are the members of the makers group when voting opens for the whole event
+    if phase == Phase::Voting {
+        let snapshot = MakersSnapshot {
+            makers_cw4_group: CONFIG.load(deps.storage)?.makers_cw4_group,
+            height: env.block.height,
+        };
+        MAKERS_SNAPSHOT.save(deps.storage, event, &snapshot)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_phase")
+        .add_attribute("from", current.to_string())
+        .add_attribute("to", phase.to_string()))
 }
 
-fn query_tally_votes(
-    deps: Deps,
-    entry_id: u8,
-    start_after: Option<String>,
-    limit: Option<u8>,
-) -> StdResult<TallyVotesResponse> {
-    let limit = limit.unwrap_or(30) as usize;
-    let start_addr = maybe_addr(deps.api, start_after)?;
-    let start = start_addr.map(Bound::exclusive);
+fn execute_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    admins_cw4_group: Option<String>,
+    makers_cw4_group: Option<String>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let mut response = Response::new().add_attribute("action", "update_config");
 
-    let mut sum = Votes {
-        look: Uint128::zero(),
-        smell: Uint128::zero(),
-        taste: Uint128::zero(),
-        post_melt: Uint128::zero(),
+    if let Some(admins_cw4_group) = admins_cw4_group {
+        let admins_cw4_group = deps.api.addr_validate(&admins_cw4_group)?;
+        response = response
+            .add_attribute("old_admins_cw4_group", &config.admins_cw4_group)
+            .add_attribute("new_admins_cw4_group", &admins_cw4_group);
+        config.admins_cw4_group = admins_cw4_group;
+    };
+    if let Some(makers_cw4_group) = makers_cw4_group {
+        let makers_cw4_group = deps.api.addr_validate(&makers_cw4_group)?;
+        response = response
+            .add_attribute("old_makers_cw4_group", &config.makers_cw4_group)
+            .add_attribute("new_makers_cw4_group", &makers_cw4_group);
+        config.makers_cw4_group = makers_cw4_group;
     };
 
-    let votes = ENTRY_VOTES
-        .prefix(entry_id)
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            let (maker_addr, votes) = item.unwrap();
-            sum.look += votes.look;
-            sum.smell += votes.smell;
-            sum.taste += votes.taste;
-            sum.post_melt += votes.post_melt;
-            VotesResponse {
-                entry_id,
-                maker_addr: maker_addr.to_string(),
-                votes: votes.clone(),
-                sum: votes.look + votes.smell + votes.taste + votes.post_melt,
-            }
-        })
-        .collect::<Vec<VotesResponse>>();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(response)
+}
 
-    let response = TallyVotesResponse { votes, sum };
+fn execute_set_voting_window(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event: u64,
+    category: Option<String>,
+    opens: Option<Scheduled>,
+    closes: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    // Windows can no longer be changed once voting has been closed
+    let current = load_phase(deps.storage, event)?.phase;
+    if current != Phase::Registration && current != Phase::Voting {
+        return Err(ContractError::WrongPhase {
+            expected: Phase::Voting,
+            current,
+        });
+    };
+
+    let voting_window = VotingWindow { opens, closes };
+    if closes_before_opening(&voting_window) {
+        return Err(ContractError::InvalidVotingWindow {});
+    };
+
+    let response = Response::new().add_attribute("action", "set_voting_window");
+    match category {
+        Some(category) => {
+            let mut category_info = CATEGORY_INFO
+                .may_load(deps.storage, (event, category.clone()))?
+                .ok_or(ContractError::InvalidCategory {})?;
+            category_info.voting_window = voting_window;
+            CATEGORY_INFO.save(deps.storage, (event, category.clone()), &category_info)?;
+
+            Ok(response.add_attribute("category", category))
+        }
+        None => {
+            VOTING_WINDOW.save(deps.storage, event, &voting_window)?;
+
+            Ok(response)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_compute_medians(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event: u64,
+    category: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    // Medians are computed once every ballot is in
+    check_phase(&deps, event, Phase::Closed)?;
+
+    let category_info = CATEGORY_INFO
+        .may_load(deps.storage, (event, category.clone()))?
+        .ok_or(ContractError::InvalidCategory {})?;
+    if category_info.scoring != ScoringMode::Median {
+        return Err(ContractError::NotMedianScoring {});
+    };
+
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let entry_ids = CATEGORY_ENTRIES
+        .prefix((event, category.clone()))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    for entry_id in &entry_ids {
+        let medians = criterion_ballots(deps.storage, &category_info, *entry_id)?
+            .into_iter()
+            .map(|(criterion, scores)| (criterion, median(scores)))
+            .collect();
+        ENTRY_MEDIANS.save(deps.storage, *entry_id, &medians)?;
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "compute_medians")
+        .add_attribute("category", category)
+        .add_attribute("count", entry_ids.len().to_string());
+    if let Some(last_entry_id) = entry_ids.last() {
+        response = response.add_attribute("last_entry_id", last_entry_id.to_string());
+    };
+    Ok(response)
+}
+
+fn execute_normalize(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    event: u64,
+    limit: Option<u8>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    // Judges are normalized once every ballot is in
+    check_phase(&deps, event, Phase::Closed)?;
+
+    let mut normalization = NORMALIZATION
+        .may_load(deps.storage, event)?
+        .unwrap_or(Normalization {
+            stage: NormalizationStage::Stats,
+            cursor: None,
+        });
+    let stage = normalization.stage;
+
+    // Empty pages would look like the end of the stage
+    let limit = limit.unwrap_or(30).max(1) as usize;
+    let start = normalization.cursor.clone().map(Bound::exclusive);
+    let ballots = ENTRY_VOTES
+        .sub_prefix(event)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    match stage {
+        // The statistics of every judge are needed before any ballot can be normalized
+        NormalizationStage::Stats => {
+            let mut global_stats = GLOBAL_STATS
+                .may_load(deps.storage, event)?
+                .unwrap_or_default();
+            for ((_, judge), votes) in &ballots {
+                let mut judge_stats = JUDGE_STATS
+                    .may_load(deps.storage, (event, judge.clone()))?
+                    .unwrap_or_default();
+                for score in votes.values() {
+                    judge_stats.add(*score);
+                    global_stats.add(*score);
+                }
+                JUDGE_STATS.save(deps.storage, (event, judge.clone()), &judge_stats)?;
+            }
+            GLOBAL_STATS.save(deps.storage, event, &global_stats)?;
+        }
+        NormalizationStage::Scores => {
+            let global_stats = GLOBAL_STATS.load(deps.storage, event)?;
+            for ((entry_id, judge), votes) in &ballots {
+                let judge_stats = JUDGE_STATS.load(deps.storage, (event, judge.clone()))?;
+                let weight =
+                    BALLOT_WEIGHTS.load(deps.storage, (event, *entry_id, judge.clone()))?;
+                let normalized = votes
+                    .iter()
+                    .map(|(criterion, score)| {
+                        (
+                            criterion.clone(),
+                            judge_stats.normalize(*score, &global_stats),
+                        )
+                    })
+                    .collect();
+
+                let mut totals = NORMALIZED_TOTALS
+                    .may_load(deps.storage, *entry_id)?
+                    .unwrap_or_default();
+                totals.add(normalized, weight);
+                NORMALIZED_TOTALS.save(deps.storage, *entry_id, &totals)?;
+            }
+        }
+        NormalizationStage::Done => return Err(ContractError::AlreadyNormalized {}),
+    };
+
+    // A short page means every ballot has been processed in this stage
+    if ballots.len() < limit {
+        normalization.stage = match stage {
+            NormalizationStage::Stats => NormalizationStage::Scores,
+            _ => NormalizationStage::Done,
+        };
+        normalization.cursor = None;
+    } else if let Some((key, _)) = ballots.last() {
+        normalization.cursor = Some(key.clone());
+    };
+    NORMALIZATION.save(deps.storage, event, &normalization)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "normalize")
+        .add_attribute("stage", stage.to_string())
+        .add_attribute("ballots", ballots.len().to_string())
+        .add_attribute("next_stage", normalization.stage.to_string()))
+}
+
+fn execute_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event: u64,
+    category: String,
+    rank_by: Option<RankBy>,
+) -> Result<Response, ContractError> {
+    // Check if the sender is in admin cw4 group
+    check_admin_membership(&deps, &info.sender)?;
+
+    // Results can be finalized once every ballot is in and revealed
+    let current = load_phase(deps.storage, event)?.phase;
+    if current != Phase::Closed && current != Phase::Finalized {
+        return Err(ContractError::WrongPhase {
+            expected: Phase::Closed,
+            current,
+        });
+    };
+
+    let category_info = CATEGORY_INFO
+        .may_load(deps.storage, (event, category.clone()))?
+        .ok_or(ContractError::InvalidCategory {})?;
+    if RESULTS.has(deps.storage, (event, category.clone())) {
+        return Err(ContractError::CategoryFinalized {});
+    };
+
+    // Medians count as zero until stored, so every entry needs them before placing
+    if category_info.scoring == ScoringMode::Median {
+        for entry_id in CATEGORY_ENTRIES.prefix((event, category.clone())).keys(
+            deps.storage,
+            None,
+            None,
+            Order::Ascending,
+        ) {
+            let entry_id = entry_id?;
+            if !ENTRY_MEDIANS.has(deps.storage, entry_id) {
+                return Err(ContractError::MediansNotComputed { entry_id });
+            };
+        }
+    };
+
+    // Normalized scores count as zero until normalization is done
+    let rank_by = rank_by.unwrap_or_default();
+    if rank_by == RankBy::Normalized && !normalization_done(deps.as_ref(), event)? {
+        return Err(ContractError::NotNormalized {});
+    };
+
+    // Placements follow the leaderboard of the category ranked the given way
+    let config = CONFIG.load(deps.storage)?;
+    let scored = score_category(deps.as_ref(), &config, &category_info, rank_by)?;
+    let placements = rank_entries(scored, usize::MAX)
+        .into_iter()
+        .map(|entry| Placement {
+            rank: entry.rank,
+            entry_id: entry.entry_id,
+            name: entry.name,
+            score: entry.score,
+            count: entry.count,
+            eligible: entry.eligible,
+            tied: entry.tied,
+        })
+        .collect();
+
+    RESULTS.save(
+        deps.storage,
+        (event, category.clone()),
+        &CategoryResults {
+            placements,
+            rank_by,
+            finalized_at_height: env.block.height,
+            finalized_at_time: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "finalize")
+        .add_attribute("category", category))
+}
+
+/// Only windows bounded by the same unit can be compared
+fn closes_before_opening(voting_window: &VotingWindow) -> bool {
+    match (&voting_window.opens, &voting_window.closes) {
+        (Some(Scheduled::AtHeight(opens)), Some(Expiration::AtHeight(closes))) => closes <= opens,
+        (Some(Scheduled::AtTime(opens)), Some(Expiration::AtTime(closes))) => closes <= opens,
+        _ => false,
+    }
+}
+
+fn check_voting_windows(
+    deps: &DepsMut,
+    block: &BlockInfo,
+    category_info: &CategoryInfo,
+) -> Result<(), ContractError> {
+    check_voting_window(
+        block,
+        &VOTING_WINDOW.load(deps.storage, category_info.event)?,
+    )?;
+    check_voting_window(block, &category_info.voting_window)
+}
+
+fn check_voting_window(
+    block: &BlockInfo,
+    voting_window: &VotingWindow,
+) -> Result<(), ContractError> {
+    if !voting_window.has_opened(block) {
+        return Err(ContractError::VotingNotOpen {});
+    };
+    if voting_window.has_closed(block) {
+        return Err(ContractError::VotingClosed {});
+    };
+
+    Ok(())
+}
+
+fn validate_criteria(criteria: &[String]) -> Result<(), ContractError> {
+    let unique = criteria.iter().collect::<BTreeSet<_>>();
+    if criteria.is_empty() || unique.len() != criteria.len() || unique.contains(&String::new()) {
+        return Err(ContractError::InvalidCriteria {});
+    };
+
+    Ok(())
+}
+
+fn validate_votes(
+    config: &Config,
+    criteria: &[String],
+    votes: &Votes,
+) -> Result<(), ContractError> {
+    if let Some(criterion) = votes.keys().find(|c| !criteria.contains(c)) {
+        return Err(ContractError::UnknownCriterion {
+            criterion: criterion.clone(),
+        });
+    };
+    if let Some(criterion) = criteria.iter().find(|c| !votes.contains_key(*c)) {
+        return Err(ContractError::MissingCriterion {
+            criterion: criterion.clone(),
+        });
+    };
+
+    for (criterion, &value) in votes {
+        // The step is only checked once the score is known to be above the minimum
+        let valid = value >= config.min_score
+            && value <= config.max_score
+            && config
+                .score_step
+                .is_none_or(|step| ((value - config.min_score) % step).is_zero());
+        if !valid {
+            return Err(ContractError::InvalidScore {
+                criterion: criterion.clone(),
+                value,
+            });
+        };
+    }
+
+    Ok(())
+}
+
+fn check_phase(deps: &DepsMut, event: u64, expected: Phase) -> Result<(), ContractError> {
+    let current = load_phase(deps.storage, event)?.phase;
+    if current != expected {
+        return Err(ContractError::WrongPhase { expected, current });
+    };
+
+    Ok(())
+}
+
+/// Loads the phase of an event, every event has one from its creation
+fn load_phase(storage: &dyn Storage, event: u64) -> Result<PhaseInfo, ContractError> {
+    PHASE
+        .may_load(storage, event)?
+        .ok_or(ContractError::InvalidEvent {})
+}
+
+fn check_admin_membership(deps: &DepsMut, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Check for admin cw4 group membership
+    let res: MemberResponse = deps.querier.query_wasm_smart(
+        config.admins_cw4_group,
+        &Cw4QueryMsg::Member {
+            addr: sender.to_string(),
+            at_height: None,
+        },
+    )?;
+    if res.weight.is_none() {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    Ok(())
+}
+
+/// Returns the weight of the ballots of the sender, 1 for every judge with equal weights.
+/// Membership is read from the makers group and height recorded when voting opened for
+/// the event
+fn check_maker_membership(deps: &DepsMut, event: u64, sender: &Addr) -> Result<u64, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (makers_cw4_group, at_height) = match MAKERS_SNAPSHOT.may_load(deps.storage, event)? {
+        Some(snapshot) => (snapshot.makers_cw4_group, Some(snapshot.height)),
+        None => (config.makers_cw4_group, None),
+    };
+
+    // Check for admin cw4 group membership
+    let res: MemberResponse = deps.querier.query_wasm_smart(
+        makers_cw4_group,
+        &Cw4QueryMsg::Member {
+            addr: sender.to_string(),
+            at_height,
+        },
+    )?;
+    let weight = res.weight.ok_or(ContractError::Unauthorized {})?;
+    if config.equal_weights {
+        return Ok(1);
+    };
+
+    // Weightless ballots would count toward quorum and trimming without adding to the score
+    if weight == 0 {
+        return Err(ContractError::Unauthorized {});
+    };
+    Ok(weight)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    // Check if the stored contract is this contract
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName {
+            name: stored.contract,
+        });
+    };
+
+    // Check if the migration is not a downgrade
+    let from: Version = stored.version.parse()?;
+    let to: Version = CONTRACT_VERSION.parse()?;
+    if from > to {
+        return Err(ContractError::CannotDowngrade {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    };
+
+    run_migrations(deps.storage, &env, &from)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", to.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Events { start_after, limit } => {
+            to_binary(&query_events(deps, start_after, limit)?)
+        }
+        QueryMsg::Event { event } => to_binary(&query_event(deps, event)?),
+        QueryMsg::Categories { event } => to_binary(&query_categories(deps, event)?),
+        QueryMsg::CategoryInfo { event, category } => {
+            to_binary(&query_category_info(deps, event, category)?)
+        }
+        QueryMsg::Entry {
+            event,
+            category,
+            entry_id,
+        } => to_binary(&query_entry(deps, event, category, entry_id)?),
+        QueryMsg::Entries {
+            event,
+            category,
+            start_after,
+            limit,
+        } => to_binary(&query_entries(deps, event, category, start_after, limit)?),
+        QueryMsg::VoteHistory {
+            event,
+            entry_id,
+            maker_addr,
+        } => to_binary(&query_vote_history(deps, event, entry_id, maker_addr)?),
+        QueryMsg::TallyVotes {
+            event,
+            entry_id,
+            start_after,
+            limit,
+        } => to_binary(&query_tally_votes(
+            deps,
+            event,
+            entry_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Votes {
+            event,
+            entry_id,
+            maker_addr,
+        } => to_binary(&query_votes(deps, event, entry_id, maker_addr)?),
+        QueryMsg::EntryTotals { event, entry_id } => {
+            to_binary(&query_entry_totals(deps, event, entry_id)?)
+        }
+        QueryMsg::EntryScores { event, entry_id } => {
+            to_binary(&query_entry_scores(deps, event, entry_id)?)
+        }
+        QueryMsg::Leaderboard {
+            event,
+            category,
+            rank_by,
+            limit,
+        } => to_binary(&query_leaderboard(deps, event, category, rank_by, limit)?),
+        QueryMsg::CriterionLeaderboard {
+            event,
+            category,
+            criterion,
+            limit,
+        } => to_binary(&query_criterion_leaderboard(
+            deps, event, category, criterion, limit,
+        )?),
+        QueryMsg::UnrevealedCommitments {
+            event,
+            entry_id,
+            start_after,
+            limit,
+        } => to_binary(&query_unrevealed_commitments(
+            deps,
+            event,
+            entry_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Phase { event } => to_binary(&query_phase(deps, event)?),
+        QueryMsg::VotingWindow { event } => to_binary(&query_voting_window(deps, event)?),
+        QueryMsg::Normalization { event } => to_binary(&query_normalization(deps, event)?),
+        QueryMsg::JudgeStats { event, judge } => to_binary(&query_judge_stats(deps, event, judge)?),
+        QueryMsg::Results { event, category } => to_binary(&query_results(deps, event, category)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<Config> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(config)
+}
+
+fn query_events(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<EventResponse>> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    EVENTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (event, event_info) = item?;
+            event_response(deps, event, event_info)
+        })
+        .collect()
+}
+
+fn query_event(deps: Deps, event: u64) -> StdResult<EventResponse> {
+    let event_info = EVENTS.load(deps.storage, event)?;
+    event_response(deps, event, event_info)
+}
+
+fn event_response(deps: Deps, event: u64, event_info: EventInfo) -> StdResult<EventResponse> {
+    Ok(EventResponse {
+        id: event,
+        data: event_info,
+        phase: PHASE.load(deps.storage, event)?.phase,
+        makers_snapshot: MAKERS_SNAPSHOT.may_load(deps.storage, event)?,
+    })
+}
+
+fn query_categories(deps: Deps, event: u64) -> StdResult<Vec<String>> {
+    let categories = CATEGORIES.load(deps.storage, event)?;
+    Ok(categories)
+}
+
+fn query_category_info(deps: Deps, event: u64, category: String) -> StdResult<CategoryInfo> {
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+    Ok(category_info)
+}
+
+fn query_voting_window(deps: Deps, event: u64) -> StdResult<VotingWindow> {
+    let voting_window = VOTING_WINDOW.load(deps.storage, event)?;
+    Ok(voting_window)
+}
+
+fn query_results(deps: Deps, event: u64, category: String) -> StdResult<ResultsResponse> {
+    let results = RESULTS.load(deps.storage, (event, category.clone()))?;
+
+    // Ineligible entries never make the podium
+    let place = |rank: u64| -> Vec<Placement> {
+        results
+            .placements
+            .iter()
+            .filter(|placement| placement.eligible && placement.rank == rank)
+            .cloned()
+            .collect()
+    };
+
+    Ok(ResultsResponse {
+        category,
+        first: place(1),
+        second: place(2),
+        third: place(3),
+        placements: results.placements.clone(),
+        rank_by: results.rank_by,
+        finalized_at_height: results.finalized_at_height,
+        finalized_at_time: results.finalized_at_time,
+    })
+}
+
+fn query_normalization(deps: Deps, event: u64) -> StdResult<Option<Normalization>> {
+    NORMALIZATION.may_load(deps.storage, event)
+}
+
+fn query_judge_stats(deps: Deps, event: u64, judge: String) -> StdResult<ScoreStats> {
+    let judge = deps.api.addr_validate(&judge)?;
+    let stats = JUDGE_STATS
+        .may_load(deps.storage, (event, judge))?
+        .unwrap_or_default();
+    Ok(stats)
+}
+
+fn query_phase(deps: Deps, event: u64) -> StdResult<PhaseInfo> {
+    let phase = PHASE.load(deps.storage, event)?;
+    Ok(phase)
+}
+
+/// Returns the category of an entry, entries of other events are not found
+fn load_entry_category(storage: &dyn Storage, event: u64, entry_id: u64) -> StdResult<String> {
+    let (entry_event, category) = ENTRY_CATEGORY.load(storage, entry_id)?;
+    if entry_event != event {
+        return Err(StdError::not_found("Entry"));
+    };
+
+    Ok(category)
+}
+
+fn query_entry(
+    deps: Deps,
+    event: u64,
+    category: Option<String>,
+    entry_id: u64,
+) -> StdResult<EntriesResponse> {
+    let entry_category = load_entry_category(deps.storage, event, entry_id)?;
+    if category.is_some_and(|category| category != entry_category) {
+        return Err(StdError::not_found("Entry"));
+    };
+
+    let config = CONFIG.load(deps.storage)?;
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, entry_category.clone()))?;
+    let entry = CATEGORY_ENTRIES.load(deps.storage, (event, entry_category, entry_id))?;
+    entry_response(deps, &config, &category_info, entry_id, entry)
+}
+
+fn query_entries(
+    deps: Deps,
+    event: u64,
+    category: String,
+    start_after: Option<u64>,
+    limit: Option<u8>,
+) -> StdResult<Vec<EntriesResponse>> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let config = CONFIG.load(deps.storage)?;
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, category.clone()))?;
+
+    let entries = CATEGORY_ENTRIES
+        .prefix((event, category))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (entry_id, entry) = item?;
+            entry_response(deps, &config, &category_info, entry_id, entry)
+        })
+        .collect::<StdResult<Vec<EntriesResponse>>>()?;
+
+    Ok(entries)
+}
+
+fn entry_response(
+    deps: Deps,
+    config: &Config,
+    category_info: &CategoryInfo,
+    entry_id: u64,
+    entry: Entry,
+) -> StdResult<EntriesResponse> {
+    let totals = ENTRY_TOTALS
+        .may_load(deps.storage, entry_id)?
+        .unwrap_or_default();
+    Ok(EntriesResponse {
+        id: entry_id,
+        data: entry,
+        ballots: totals.count,
+        eligible: category_info.is_eligible(config, &totals),
+    })
+}
+
+fn query_votes(deps: Deps, event: u64, entry_id: u64, maker_addr: String) -> StdResult<Votes> {
+    let addr = deps.api.addr_validate(&maker_addr)?;
+    let votes = ENTRY_VOTES.load(deps.storage, (event, entry_id, addr))?;
+    Ok(votes)
+}
+
+fn query_vote_history(
+    deps: Deps,
+    event: u64,
+    entry_id: u64,
+    maker_addr: String,
+) -> StdResult<Vec<VoteRecord>> {
+    let addr = deps.api.addr_validate(&maker_addr)?;
+
+    // Check if the entry belongs to the event
+    load_entry_category(deps.storage, event, entry_id)?;
+
+    VOTE_HISTORY
+        .prefix((entry_id, addr))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
+
+fn query_entry_totals(deps: Deps, event: u64, entry_id: u64) -> StdResult<EntryTotals> {
+    // Check if the entry exists, entries without votes have no totals yet
+    load_entry_category(deps.storage, event, entry_id)?;
+
+    let totals = ENTRY_TOTALS
+        .may_load(deps.storage, entry_id)?
+        .unwrap_or_default();
+    Ok(totals)
+}
+
+fn query_leaderboard(
+    deps: Deps,
+    event: u64,
+    category: String,
+    rank_by: Option<RankBy>,
+    limit: Option<u8>,
+) -> StdResult<Vec<LeaderboardResponse>> {
+    let limit = limit.unwrap_or(30) as usize;
+    let rank_by = rank_by.unwrap_or_default();
+
+    let config = CONFIG.load(deps.storage)?;
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+
+    let scored = score_category(deps, &config, &category_info, rank_by)?;
+    Ok(rank_entries(scored, limit))
+}
+
+/// Scores every entry of a category, breaking ties with the tie-breakers of the category
+fn score_category(
+    deps: Deps,
+    config: &Config,
+    category_info: &CategoryInfo,
+    rank_by: RankBy,
+) -> StdResult<Vec<ScoredEntry>> {
+    load_category_entries(
+        deps,
+        config,
+        category_info,
+        &category_info.tie_breakers,
+        |entry_id, totals, scores| match rank_by {
+            RankBy::Total => Ok(Decimal::from_ratio(totals.total, 1u128)),
+            RankBy::WeightedTotal => Ok(totals.weighted_total),
+            RankBy::Average => Ok(entry_score(category_info, totals, scores)),
+            RankBy::Normalized => {
+                Ok(normalized_score(deps, category_info.event, entry_id)?.unwrap_or_default())
+            }
+        },
+    )
+}
+
+fn query_criterion_leaderboard(
+    deps: Deps,
+    event: u64,
+    category: Option<String>,
+    criterion: String,
+    limit: Option<u8>,
+) -> StdResult<Vec<LeaderboardResponse>> {
+    let limit = limit.unwrap_or(30) as usize;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Ranks a single category if given, otherwise every category judging the criterion
+    let categories = match category {
+        Some(category) => {
+            let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+            if !category_info.criteria.contains(&criterion) {
+                return Err(StdError::generic_err(format!(
+                    "Unknown criterion: {criterion}"
+                )));
+            };
+            vec![category_info]
+        }
+        None => {
+            let mut categories = vec![];
+            for category in CATEGORIES.load(deps.storage, event)? {
+                let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+                if category_info.criteria.contains(&criterion) {
+                    categories.push(category_info);
+                }
+            }
+            categories
+        }
+    };
+
+    // Tie-breaking criteria differ between categories, so only the number of ballots and
+    // the submissions break ties here
+    let mut scored = vec![];
+    for category_info in categories {
+        scored.extend(load_category_entries(
+            deps,
+            &config,
+            &category_info,
+            &[],
+            |_, _, scores| Ok(scores.get(&criterion).copied().unwrap_or_default()),
+        )?);
+    }
+
+    Ok(rank_entries(scored, limit))
+}
+
+fn query_entry_scores(deps: Deps, event: u64, entry_id: u64) -> StdResult<EntryScoresResponse> {
+    let category = load_entry_category(deps.storage, event, entry_id)?;
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+    let totals = ENTRY_TOTALS
+        .may_load(deps.storage, entry_id)?
+        .unwrap_or_default();
+    let scored = criterion_scores(
+        deps,
+        &category_info,
+        category_info.scoring,
+        entry_id,
+        &totals,
+    )?;
+
+    Ok(EntryScoresResponse {
+        entry_id,
+        scoring: category_info.scoring,
+        count: totals.count,
+        raw: criterion_scores(deps, &category_info, ScoringMode::Mean, entry_id, &totals)?,
+        raw_score: totals.weighted_average(),
+        score: entry_score(&category_info, &totals, &scored),
+        scored,
+        normalized_score: normalized_score(deps, event, entry_id)?,
+    })
+}
+
+fn normalization_done(deps: Deps, event: u64) -> StdResult<bool> {
+    Ok(NORMALIZATION
+        .may_load(deps.storage, event)?
+        .is_some_and(|normalization| normalization.stage == NormalizationStage::Done))
+}
+
+/// Returns the mean of the normalized ballots of an entry once normalization is done
+fn normalized_score(deps: Deps, event: u64, entry_id: u64) -> StdResult<Option<Decimal>> {
+    if !normalization_done(deps, event)? {
+        return Ok(None);
+    };
+
+    let totals = NORMALIZED_TOTALS
+        .may_load(deps.storage, entry_id)?
+        .unwrap_or_default();
+    Ok(Some(totals.average()))
+}
+
+/// Returns the score of every criterion of an entry under the given scoring mode
+fn criterion_scores(
+    deps: Deps,
+    category_info: &CategoryInfo,
+    scoring: ScoringMode,
+    entry_id: u64,
+    totals: &EntryTotals,
+) -> StdResult<BTreeMap<String, Decimal>> {
+    match scoring {
+        // Means are kept up to date in the totals
+        ScoringMode::Mean => Ok(category_info
+            .criteria
+            .iter()
+            .map(|criterion| (criterion.clone(), totals.criterion_average(criterion)))
+            .collect()),
+        // Only the ballots to drop are read, the rest comes from the totals
+        ScoringMode::TrimmedMean { trim } => category_info
+            .criteria
+            .iter()
+            .map(|criterion| {
+                let score = trimmed_mean(deps.storage, entry_id, criterion, trim, totals)?;
+                Ok((criterion.clone(), score))
+            })
+            .collect(),
+        // Medians are too costly to compute over every ballot in a query, so they are
+        // only available once stored and count as zero until then
+        ScoringMode::Median => {
+            let medians = ENTRY_MEDIANS.may_load(deps.storage, entry_id)?;
+            Ok(category_info
+                .criteria
+                .iter()
+                .map(|criterion| {
+                    let median = medians.as_ref().and_then(|medians| medians.get(criterion));
+                    (criterion.clone(), median.copied().unwrap_or_default())
+                })
+                .collect())
+        }
+    }
+}
+
+/// Returns the weighted mean of a criterion without its `trim` lowest and highest ballots,
+/// or its mean if there are too few ballots to trim
+fn trimmed_mean(
+    storage: &dyn Storage,
+    entry_id: u64,
+    criterion: &str,
+    trim: u32,
+    totals: &EntryTotals,
+) -> StdResult<Decimal> {
+    if totals.count <= trim as u64 * 2 {
+        return Ok(totals.criterion_average(criterion));
+    };
+
+    let mut sum = totals.sums.get(criterion).copied().unwrap_or_default();
+    let mut weight = totals.weight;
+    for order in [Order::Ascending, Order::Descending] {
+        for item in SORTED_SCORES
+            .sub_prefix((entry_id, criterion.to_string()))
+            .range(storage, None, None, order)
+            .take(trim as usize)
+        {
+            let ((score, _), ballot_weight) = item?;
+            sum -= Uint128::new(score) * Uint128::from(ballot_weight);
+            weight -= ballot_weight;
+        }
+    }
+    if weight == 0 {
+        return Ok(Decimal::zero());
+    };
+    Ok(Decimal::from_ratio(sum, weight))
+}
+
+/// Returns the scores every ballot of an entry gave to each criterion along with the
+/// weight of the ballot
+fn criterion_ballots(
+    storage: &dyn Storage,
+    category_info: &CategoryInfo,
+    entry_id: u64,
+) -> StdResult<BTreeMap<String, Vec<(Uint128, u64)>>> {
+    let mut scores: BTreeMap<String, Vec<(Uint128, u64)>> = category_info
+        .criteria
+        .iter()
+        .map(|criterion| (criterion.clone(), vec![]))
+        .collect();
+    for item in ENTRY_VOTES.prefix((category_info.event, entry_id)).range(
+        storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (judge, votes) = item?;
+        let weight = BALLOT_WEIGHTS.load(storage, (category_info.event, entry_id, judge))?;
+        for (criterion, score) in votes {
+            scores.entry(criterion).or_default().push((score, weight));
+        }
+    }
+
+    Ok(scores)
+}
+
+/// Returns the sum of the criterion scores of an entry under the scoring mode of its
+/// category, each multiplied by the weight of its criterion
+fn entry_score(
+    category_info: &CategoryInfo,
+    totals: &EntryTotals,
+    scores: &BTreeMap<String, Decimal>,
+) -> Decimal {
+    if let ScoringMode::Mean = category_info.scoring {
+        return totals.weighted_average();
+    };
+    category_info.weighted_score(scores)
+}
+
+struct ScoredEntry {
+    entry_id: u64,
+    entry: Entry,
+    totals: EntryTotals,
+    eligible: bool,
+    score: Decimal,
+    // Scores of the tie-breaking criteria in order of priority
+    tie_breaks: Vec<Decimal>,
+    submitted: u64,
+}
+
+impl ScoredEntry {
+    /// Entries are ranked by score, then by their tie-breaking criteria, then by the
+    /// number of ballots and then by the earliest submission
+    fn ranking_key(
+        &self,
+    ) -> (
+        bool,
+        Reverse<Decimal>,
+        Reverse<&[Decimal]>,
+        Reverse<u64>,
+        u64,
+    ) {
+        (
+            !self.eligible,
+            Reverse(self.score),
+            Reverse(&self.tie_breaks),
+            Reverse(self.totals.count),
+            self.submitted,
+        )
+    }
+}
+
+/// Loads every entry of a category along with its totals and scores it, given the criterion
+/// scores of the entry under the scoring mode of the category
+fn load_category_entries(
+    deps: Deps,
+    config: &Config,
+    category_info: &CategoryInfo,
+    tie_breakers: &[String],
+    score: impl Fn(u64, &EntryTotals, &BTreeMap<String, Decimal>) -> StdResult<Decimal>,
+) -> StdResult<Vec<ScoredEntry>> {
+    CATEGORY_ENTRIES
+        .prefix((category_info.event, category_info.name.clone()))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (entry_id, entry) = item?;
+            let totals = ENTRY_TOTALS
+                .may_load(deps.storage, entry_id)?
+                .unwrap_or_default();
+
+            // Scores are loaded once for ranking and tie-breaking alike
+            let scores = criterion_scores(
+                deps,
+                category_info,
+                category_info.scoring,
+                entry_id,
+                &totals,
+            )?;
+            let tie_breaks = tie_breakers
+                .iter()
+                .map(|criterion| scores.get(criterion).copied().unwrap_or_default())
+                .collect();
+
+            Ok(ScoredEntry {
+                entry_id,
+                entry,
+                eligible: category_info.is_eligible(config, &totals),
+                score: score(entry_id, &totals, &scores)?,
+                tie_breaks,
+                submitted: ENTRY_SUBMITTED
+                    .may_load(deps.storage, entry_id)?
+                    .unwrap_or_default(),
+                totals,
+            })
+        })
+        .collect()
+}
+
+/// Ranks eligible entries from the highest score to the lowest, followed by the
+/// ineligible ones. Entries still level after every tie-break share a rank
+fn rank_entries(mut scored: Vec<ScoredEntry>, limit: usize) -> Vec<LeaderboardResponse> {
+    // Entries that are fully tied are listed in ID order
+    scored.sort_by(|a, b| {
+        a.ranking_key()
+            .cmp(&b.ranking_key())
+            .then(a.entry_id.cmp(&b.entry_id))
+    });
+
+    let mut leaderboard: Vec<LeaderboardResponse> = vec![];
+    for (index, current) in scored.iter().enumerate().take(limit) {
+        let level_with = |other: Option<&ScoredEntry>| {
+            other.is_some_and(|other| other.ranking_key() == current.ranking_key())
+        };
+        let level_with_previous = level_with(index.checked_sub(1).map(|i| &scored[i]));
+        let level_with_next = level_with(scored.get(index + 1));
+
+        let rank = match leaderboard.last() {
+            Some(previous) if level_with_previous => previous.rank,
+            _ => index as u64 + 1,
+        };
+        leaderboard.push(LeaderboardResponse {
+            rank,
+            entry_id: current.entry_id,
+            name: current.entry.name.clone(),
+            category: current.entry.category.clone(),
+            score: current.score,
+            count: current.totals.count,
+            sums: current.totals.sums.clone(),
+            eligible: current.eligible,
+            tied: level_with_previous || level_with_next,
+        });
+    }
+
+    leaderboard
+}
+
+fn query_unrevealed_commitments(
+    deps: Deps,
+    event: u64,
+    entry_id: u64,
+    start_after: Option<String>,
+    limit: Option<u8>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.map(Bound::exclusive);
+
+    // Check if the entry belongs to the event
+    load_entry_category(deps.storage, event, entry_id)?;
+
+    COMMITMENTS
+        .prefix(entry_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|maker_addr| maker_addr.to_string()))
+        .collect()
+}
+
+fn query_tally_votes(
+    deps: Deps,
+    event: u64,
+    entry_id: u64,
+    start_after: Option<String>,
+    limit: Option<u8>,
+) -> StdResult<TallyVotesResponse> {
+    let limit = limit.unwrap_or(30) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.map(Bound::exclusive);
+
+    let category = load_entry_category(deps.storage, event, entry_id)?;
+    let category_info = CATEGORY_INFO.load(deps.storage, (event, category))?;
+
+    let mut sum = Votes::new();
+    let mut weighted_sum = Decimal::zero();
+
+    let votes = ENTRY_VOTES
+        .prefix((event, entry_id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (maker_addr, votes) = item?;
+            let weight =
+                BALLOT_WEIGHTS.load(deps.storage, (event, entry_id, maker_addr.clone()))?;
+            for (criterion, score) in &votes {
+                *sum.entry(criterion.clone()).or_default() += *score * Uint128::from(weight);
+            }
+            let votes_weighted_sum = category_info.weighted_sum(&votes);
+            weighted_sum += votes_weighted_sum * Decimal::from_ratio(weight, 1u128);
+            Ok(VotesResponse {
+                entry_id,
+                maker_addr: maker_addr.to_string(),
+                weight,
+                sum: votes.values().sum(),
+                weighted_sum: votes_weighted_sum,
+                votes,
+            })
+        })
+        .collect::<StdResult<Vec<VotesResponse>>>()?;
+
+    let response = TallyVotesResponse {
+        votes,
+        sum,
+        weighted_sum,
+    };
 
     Ok(response)
 }
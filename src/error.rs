@@ -1,6 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
+use crate::state::Phase;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -9,9 +11,103 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("Cannot migrate from contract {name}")]
+    InvalidContractName { name: String },
+
+    #[error("Cannot migrate from version {from} to older version {to}")]
+    CannotDowngrade { from: String, to: String },
+
     #[error("Invalid maker address")]
     InvalidMaker {},
 
+    #[error("Invalid event")]
+    InvalidEvent {},
+
     #[error("Invalid category")]
     InvalidCategory {},
+
+    #[error("Invalid criteria")]
+    InvalidCriteria {},
+
+    #[error("Unknown criterion: {criterion}")]
+    UnknownCriterion { criterion: String },
+
+    #[error("Missing score for {criterion}")]
+    MissingCriterion { criterion: String },
+
+    #[error("Invalid score range")]
+    InvalidScoreRange {},
+
+    #[error("Invalid score for {criterion}: {value}")]
+    InvalidScore { criterion: String, value: Uint128 },
+
+    #[error("Not allowed in the {current} phase, expected the {expected} phase")]
+    WrongPhase { expected: Phase, current: Phase },
+
+    #[error("Cannot move from the {from} phase to the {to} phase")]
+    InvalidPhaseTransition { from: Phase, to: Phase },
+
+    #[error("Voting window closes before it opens")]
+    InvalidVotingWindow {},
+
+    #[error("Votes must be committed and revealed")]
+    CommitRevealEnabled {},
+
+    #[error("Commit and reveal is not enabled")]
+    CommitRevealDisabled {},
+
+    #[error("Ballot cannot be amended more than {max} times")]
+    TooManyAmendments { max: u32 },
+
+    #[error("Entry is scored more than once")]
+    DuplicateEntry {},
+
+    #[error("Batch has no ballots")]
+    EmptyBatch {},
+
+    #[error("Invalid ballot {index} for entry {entry_id}: {source}")]
+    InvalidBallot {
+        index: usize,
+        entry_id: u64,
+        source: Box<ContractError>,
+    },
+
+    #[error("No vote to retract")]
+    NoVote {},
+
+    #[error("No committed vote to reveal")]
+    NoCommitment {},
+
+    #[error("Revealed vote does not match the commitment")]
+    CommitmentMismatch {},
+
+    #[error("Voting has not opened yet")]
+    VotingNotOpen {},
+
+    #[error("Voting has already closed")]
+    VotingClosed {},
+
+    #[error("Category is not scored by median")]
+    NotMedianScoring {},
+
+    #[error("Scores are already normalized")]
+    AlreadyNormalized {},
+
+    #[error("Scores are not normalized yet")]
+    NotNormalized {},
+
+    #[error("Medians are not computed for entry {entry_id}")]
+    MediansNotComputed { entry_id: u64 },
+
+    #[error("Results of the category are final")]
+    CategoryFinalized {},
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
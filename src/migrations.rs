@@ -0,0 +1,181 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Env, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use semver::Version;
+
+use crate::contract::{DEFAULT_MAX_SCORE, DEFAULT_MIN_BALLOTS, DEFAULT_MIN_SCORE};
+use crate::state::{
+    CategoryInfo, Config, Entry, EventInfo, MakersSnapshot, Phase, PhaseInfo, ScoringMode,
+    VoteRecord, Votes, VotingWindow, BALLOT_WEIGHTS, CATEGORIES, CATEGORY_ENTRIES, CATEGORY_INFO,
+    CONFIG, DEFAULT_CRITERIA, ENTRY_CATEGORY, ENTRY_SUBMITTED, ENTRY_TOTALS, ENTRY_VOTES, EVENTS,
+    EVENT_ID, MAKERS_SNAPSHOT, PHASE, VOTE_HISTORY, VOTING_WINDOW,
+};
+
+type MigrationStep = fn(&mut dyn Storage, &Env) -> StdResult<()>;
+
+// Target version -> Step migrating the state of the version before it, in version order
+const MIGRATIONS: &[(Version, MigrationStep)] = &[(Version::new(0, 2, 0), migrate_to_v0_2)];
+
+/// Runs every migration step targeting a version newer than the stored one
+pub fn run_migrations(storage: &mut dyn Storage, env: &Env, from: &Version) -> StdResult<()> {
+    for (version, step) in MIGRATIONS {
+        if from < version {
+            step(storage, env)?;
+        };
+    }
+
+    Ok(())
+}
+
+#[cw_serde]
+struct ConfigV0_1 {
+    admins_cw4_group: Addr,
+    makers_cw4_group: Addr,
+}
+const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+
+// Vector of category names
+const CATEGORIES_V0_1: Item<Vec<String>> = Item::new("categories");
+
+// (Category Names, Entry IDs) -> Entry
+const CATEGORY_ENTRIES_V0_1: Map<(String, u8), Entry> = Map::new("category_entries");
+
+// (Entry IDs, Maker Addr) -> Votes
+const ENTRY_VOTES_V0_1: Map<(u8, Addr), Votes> = Map::new("entry_votes");
+
+// Version 0.1.0 hosted a single event, which becomes the first event
+const LEGACY_EVENT: u64 = 1;
+const LEGACY_EVENT_NAME: &str = "Legends 2022";
+
+/// Migrates the state of version 0.1.0, which hosted a single event, stored entry IDs
+/// as u8 and had no phases, voting windows, score ranges, category settings, entry
+/// index or totals
+fn migrate_to_v0_2(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let config = CONFIG_V0_1.load(storage)?;
+    CONFIG.save(
+        storage,
+        &Config {
+            admins_cw4_group: config.admins_cw4_group,
+            makers_cw4_group: config.makers_cw4_group,
+            min_score: Uint128::new(DEFAULT_MIN_SCORE),
+            max_score: Uint128::new(DEFAULT_MAX_SCORE),
+            score_step: None,
+            commit_reveal: false,
+            min_ballots: DEFAULT_MIN_BALLOTS,
+            max_amendments: None,
+            equal_weights: false,
+        },
+    )?;
+
+    EVENTS.save(
+        storage,
+        LEGACY_EVENT,
+        &EventInfo {
+            name: LEGACY_EVENT_NAME.to_string(),
+            date: None,
+            location: None,
+        },
+    )?;
+    EVENT_ID.save(storage, &LEGACY_EVENT)?;
+
+    let categories = CATEGORIES_V0_1.load(storage)?;
+    CATEGORIES_V0_1.remove(storage);
+    CATEGORIES.save(storage, LEGACY_EVENT, &categories)?;
+
+    // Old and new keys share a namespace, so every old key is read before any is rewritten
+    let entries = CATEGORY_ENTRIES_V0_1
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let votes = ENTRY_VOTES_V0_1
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((category, entry_id), entry) in entries {
+        CATEGORY_ENTRIES_V0_1.remove(storage, (category.clone(), entry_id));
+        CATEGORY_ENTRIES.save(
+            storage,
+            (LEGACY_EVENT, category.clone(), entry_id.into()),
+            &entry,
+        )?;
+        ENTRY_CATEGORY.save(storage, entry_id.into(), &(LEGACY_EVENT, category))?;
+        // Submission heights were not recorded, so existing entries tie on submission
+        ENTRY_SUBMITTED.save(storage, entry_id.into(), &env.block.height)?;
+    }
+    for ((entry_id, maker_addr), entry_votes) in &votes {
+        ENTRY_VOTES_V0_1.remove(storage, (*entry_id, maker_addr.clone()));
+        ENTRY_VOTES.save(
+            storage,
+            (LEGACY_EVENT, (*entry_id).into(), maker_addr.clone()),
+            entry_votes,
+        )?;
+        // Version 0.1.0 counted every ballot once
+        BALLOT_WEIGHTS.save(
+            storage,
+            (LEGACY_EVENT, (*entry_id).into(), maker_addr.clone()),
+            &1,
+        )?;
+        // Submission blocks were not recorded, so existing ballots are dated at the migration
+        VOTE_HISTORY.save(
+            storage,
+            ((*entry_id).into(), maker_addr.clone(), 0),
+            &VoteRecord {
+                votes: entry_votes.clone(),
+                height: env.block.height,
+                time: env.block.time,
+            },
+        )?;
+    }
+
+    // Every existing category is judged on the default criteria with equal weights
+    for category in categories {
+        let category_info = CategoryInfo {
+            event: LEGACY_EVENT,
+            name: category.clone(),
+            criteria: DEFAULT_CRITERIA.iter().map(|c| c.to_string()).collect(),
+            weights: DEFAULT_CRITERIA
+                .iter()
+                .map(|c| (c.to_string(), Decimal::one()))
+                .collect(),
+            voting_window: VotingWindow::default(),
+            min_ballots: None,
+            scoring: ScoringMode::default(),
+            tie_breakers: vec![],
+        };
+        CATEGORY_INFO.save(storage, (LEGACY_EVENT, category), &category_info)?;
+    }
+    VOTING_WINDOW.save(storage, LEGACY_EVENT, &VotingWindow::default())?;
+
+    for ((entry_id, _), entry_votes) in &votes {
+        let entry_id = (*entry_id).into();
+        let category_info = CATEGORY_INFO.load(storage, ENTRY_CATEGORY.load(storage, entry_id)?)?;
+        let mut totals = ENTRY_TOTALS
+            .may_load(storage, entry_id)?
+            .unwrap_or_default();
+        totals.add(entry_votes, category_info.weighted_sum(entry_votes), 1);
+        ENTRY_TOTALS.save(storage, entry_id, &totals)?;
+    }
+
+    // Version 0.1.0 accepted votes at any time, so voting stays open if it has started
+    let phase = if votes.is_empty() {
+        Phase::Registration
+    } else {
+        // Judges are frozen from the migration on, like an event that just opened voting
+        let snapshot = MakersSnapshot {
+            makers_cw4_group: CONFIG.load(storage)?.makers_cw4_group,
+            height: env.block.height,
+        };
+        MAKERS_SNAPSHOT.save(storage, LEGACY_EVENT, &snapshot)?;
+        Phase::Voting
+    };
+    PHASE.save(
+        storage,
+        LEGACY_EVENT,
+        &PhaseInfo {
+            phase,
+            changed_at_height: env.block.height,
+            changed_at_time: env.block.time,
+        },
+    )?;
+
+    Ok(())
+}
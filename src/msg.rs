@@ -1,20 +1,62 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use std::collections::BTreeMap;
 
-use crate::state::{Entry, Votes};
+use cosmwasm_std::{Binary, Decimal, Timestamp, Uint128};
+use cw_utils::{Expiration, Scheduled};
+
+use crate::state::{
+    CategoryInfo, Config, Entry, EntryTotals, EventInfo, MakersSnapshot, Normalization, Phase,
+    PhaseInfo, Placement, RankBy, ScoreStats, ScoringMode, VoteRecord, Votes, VotingWindow,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admins_cw4_group: String,
     pub makers_cw4_group: String,
+    /// Lowest accepted score, defaults to 100 (1.00)
+    pub min_score: Option<Uint128>,
+    /// Highest accepted score, defaults to 1000 (10.00)
+    pub max_score: Option<Uint128>,
+    /// If set, scores must be a multiple of this step above `min_score`
+    pub score_step: Option<Uint128>,
+    /// If true, votes are committed as hashes while voting and revealed afterwards
+    pub commit_reveal: Option<bool>,
+    /// Ballots an entry needs to be eligible for the results, defaults to 1
+    pub min_ballots: Option<u64>,
+    /// Times a judge can amend a ballot, unlimited by default and final if zero
+    pub max_amendments: Option<u32>,
+    /// If true, every ballot counts once instead of by the cw4 weight of the judge
+    pub equal_weights: Option<bool>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
+    /// Creates an event in the registration phase, such as a yearly edition
+    CreateEvent {
+        name: String,
+        date: Option<String>,
+        location: Option<String>,
+    },
     AddCategory {
+        event: u64,
         category: String,
+        /// Names of the criteria judged in this category, defaults to look, smell, taste and post_melt
+        criteria: Option<Vec<String>>,
+        /// Criterion name to weight, criteria left out are weighted 1
+        weights: Option<BTreeMap<String, Decimal>>,
+        /// Overrides the quorum of the config for this category
+        min_ballots: Option<u64>,
+        /// How ballots are aggregated into entry scores, defaults to the mean
+        scoring: Option<ScoringMode>,
+        /// Criteria that break ties in order of priority, ties left after them go to the
+        /// entry with the most ballots and then to the earliest submission
+        tie_breakers: Option<Vec<String>>,
     },
     AddEntry {
+        event: u64,
         name: String,
         category: String,
         maker_addr: String,
@@ -23,52 +65,259 @@ pub enum ExecuteMsg {
         genetics: String,
         farmer: String,
     },
+    /// The category is optional and only checked against the entry if given
     Vote {
-        category: String,
-        entry_id: u8,
+        event: u64,
+        category: Option<String>,
+        entry_id: u64,
+        votes: Votes,
+    },
+    /// Votes on several entries at once, every ballot is checked before any is saved and
+    /// a single invalid ballot rejects the whole batch
+    VoteBatch {
+        event: u64,
+        ballots: Vec<Ballot>,
+    },
+    /// Commits to a vote with sha256(votes || salt), where votes is the JSON encoded ballot
+    CommitVote {
+        event: u64,
+        category: Option<String>,
+        entry_id: u64,
+        commitment: Binary,
+    },
+    RevealVote {
+        event: u64,
+        category: Option<String>,
+        entry_id: u64,
         votes: Votes,
+        salt: String,
+    },
+    /// Withdraws the ballot of the sender for an entry while voting is open, or the
+    /// commitment with commit and reveal
+    RetractVote {
+        event: u64,
+        entry_id: u64,
+    },
+    SetPhase {
+        event: u64,
+        phase: Phase,
+    },
+    UpdateConfig {
+        admins_cw4_group: Option<String>,
+        makers_cw4_group: Option<String>,
+    },
+    /// Sets the event wide voting window, or the window of a single category if given
+    SetVotingWindow {
+        event: u64,
+        category: Option<String>,
+        opens: Option<Scheduled>,
+        closes: Option<Expiration>,
+    },
+    /// Computes and stores the medians of the entries of a median scored category once
+    /// voting has closed, page by page
+    ComputeMedians {
+        event: u64,
+        category: String,
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    /// Normalizes the scores of every judge once voting has closed, processing up to
+    /// `limit` ballots per call until normalization is done
+    Normalize {
+        event: u64,
+        limit: Option<u8>,
+    },
+    /// Stores the final placements of a category once voting is closed
+    Finalize {
+        event: u64,
+        category: String,
+        rank_by: Option<RankBy>,
     },
 }
 
+#[cw_serde]
+pub struct Ballot {
+    pub entry_id: u64,
+    pub votes: Votes,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    /// Returns every event in ID order
+    #[returns(Vec<EventResponse>)]
+    Events {
+        start_after: Option<u64>,
+        limit: Option<u8>,
+    },
+    #[returns(EventResponse)]
+    Event { event: u64 },
     #[returns(Vec<String>)]
-    Categories {},
-    #[returns(Entry)]
-    Entry { category: String, entry_id: u8 },
+    Categories { event: u64 },
+    #[returns(CategoryInfo)]
+    CategoryInfo { event: u64, category: String },
+    #[returns(EntriesResponse)]
+    Entry {
+        event: u64,
+        category: Option<String>,
+        entry_id: u64,
+    },
     #[returns(Vec<EntriesResponse>)]
     Entries {
+        event: u64,
         category: String,
-        start_after: Option<u8>,
+        start_after: Option<u64>,
         limit: Option<u8>,
     },
     #[returns(Votes)]
-    Votes { entry_id: u8, maker_addr: String },
-    #[returns(Vec<TallyVotesResponse>)]
+    Votes {
+        event: u64,
+        entry_id: u64,
+        maker_addr: String,
+    },
+    /// Returns every ballot a judge submitted for an entry, from the first to the latest
+    #[returns(Vec<VoteRecord>)]
+    VoteHistory {
+        event: u64,
+        entry_id: u64,
+        maker_addr: String,
+    },
+    /// Returns the votes of an entry page by page, with sums over the returned page
+    #[returns(TallyVotesResponse)]
     TallyVotes {
-        entry_id: u8,
+        event: u64,
+        entry_id: u64,
+        start_after: Option<String>,
+        limit: Option<u8>,
+    },
+    /// Returns the totals over every vote of an entry
+    #[returns(EntryTotals)]
+    EntryTotals { event: u64, entry_id: u64 },
+    /// Returns the raw mean of every criterion of an entry next to its score under the
+    /// scoring mode of the category
+    #[returns(EntryScoresResponse)]
+    EntryScores { event: u64, entry_id: u64 },
+    /// Returns the entries of a category ranked from the highest score to the lowest
+    #[returns(Vec<LeaderboardResponse>)]
+    Leaderboard {
+        event: u64,
+        category: String,
+        rank_by: Option<RankBy>,
+        limit: Option<u8>,
+    },
+    /// Returns the entries of a category, or of every category judging the criterion,
+    /// ranked by their mean score for a single criterion
+    #[returns(Vec<LeaderboardResponse>)]
+    CriterionLeaderboard {
+        event: u64,
+        category: Option<String>,
+        criterion: String,
+        limit: Option<u8>,
+    },
+    /// Returns the voters that committed to a vote for the entry without revealing it
+    #[returns(Vec<String>)]
+    UnrevealedCommitments {
+        event: u64,
+        entry_id: u64,
         start_after: Option<String>,
         limit: Option<u8>,
     },
+    #[returns(PhaseInfo)]
+    Phase { event: u64 },
+    #[returns(VotingWindow)]
+    VotingWindow { event: u64 },
+    #[returns(Option<Normalization>)]
+    Normalization { event: u64 },
+    #[returns(ScoreStats)]
+    JudgeStats { event: u64, judge: String },
+    /// Returns the final placements of a category once finalized
+    #[returns(ResultsResponse)]
+    Results { event: u64, category: String },
+}
+
+#[cw_serde]
+pub struct EventResponse {
+    pub id: u64,
+    pub data: EventInfo,
+    pub phase: Phase,
+    /// Makers group and block height the judges of the event are taken from, set once
+    /// voting opens
+    pub makers_snapshot: Option<MakersSnapshot>,
 }
 
 #[cw_serde]
 pub struct EntriesResponse {
-    pub id: u8,
+    pub id: u64,
     pub data: Entry,
+    /// Number of ballots cast for the entry
+    pub ballots: u64,
+    /// False until the entry reaches the quorum of its category
+    pub eligible: bool,
 }
 
 #[cw_serde]
 pub struct VotesResponse {
-    pub entry_id: u8,
+    pub entry_id: u64,
     pub maker_addr: String,
+    /// Weight of the judge when the ballot was cast
+    pub weight: u64,
     pub votes: Votes,
     pub sum: Uint128,
+    pub weighted_sum: Decimal,
 }
 
 #[cw_serde]
 pub struct TallyVotesResponse {
     pub votes: Vec<VotesResponse>,
+    /// Sums of the page with every ballot multiplied by its weight
     pub sum: Votes,
+    pub weighted_sum: Decimal,
+}
+
+#[cw_serde]
+pub struct EntryScoresResponse {
+    pub entry_id: u64,
+    pub scoring: ScoringMode,
+    pub count: u64,
+    /// Criterion Name -> Mean of every ballot
+    pub raw: BTreeMap<String, Decimal>,
+    /// Sum of the raw criterion scores multiplied by the weight of their criterion
+    pub raw_score: Decimal,
+    /// Criterion Name -> Score under the scoring mode
+    pub scored: BTreeMap<String, Decimal>,
+    /// Sum of the scored criterion scores multiplied by the weight of their criterion
+    pub score: Decimal,
+    /// Mean of the normalized ballots, once normalization is done
+    pub normalized_score: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct LeaderboardResponse {
+    /// Entries that are still level after every tie-break share a rank
+    pub rank: u64,
+    pub entry_id: u64,
+    pub name: String,
+    pub category: String,
+    pub score: Decimal,
+    pub count: u64,
+    pub sums: Votes,
+    /// Ineligible entries are ranked after every eligible entry
+    pub eligible: bool,
+    /// True if the entry still shares its rank after every tie-break
+    pub tied: bool,
+}
+
+#[cw_serde]
+pub struct ResultsResponse {
+    pub category: String,
+    /// Eligible entries in first place, more than one if tied
+    pub first: Vec<Placement>,
+    pub second: Vec<Placement>,
+    pub third: Vec<Placement>,
+    pub placements: Vec<Placement>,
+    pub rank_by: RankBy,
+    pub finalized_at_height: u64,
+    pub finalized_at_time: Timestamp,
 }
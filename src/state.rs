@@ -1,21 +1,35 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use cosmwasm_std::{Addr, Binary, BlockInfo, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::{Expiration, Scheduled};
 
 #[cw_serde]
 pub struct Config {
     pub admins_cw4_group: Addr,
     pub makers_cw4_group: Addr,
+    pub min_score: Uint128,
+    pub max_score: Uint128,
+    pub score_step: Option<Uint128>,
+    pub commit_reveal: bool,
+    /// Ballots an entry needs to be eligible, unless its category sets its own quorum
+    pub min_ballots: u64,
+    /// Times a judge can amend a ballot, unlimited if not set and final if zero
+    pub max_amendments: Option<u32>,
+    /// Counts every ballot once instead of by the cw4 weight of the judge
+    pub equal_weights: bool,
 }
 pub const CONFIG: Item<Config> = Item::new("config");
 
-#[cw_serde]
-pub struct Votes {
-    pub look: Uint128,
-    pub smell: Uint128,
-    pub taste: Uint128,
-    pub post_melt: Uint128,
-}
+// Criterion Name -> Score
+// Ballots stored before criteria became configurable were structs with the default
+// four criteria as fields, which serialize the same way and still load as this map
+pub type Votes = BTreeMap<String, Uint128>;
+
+// Criteria used by categories that do not define their own
+pub const DEFAULT_CRITERIA: [&str; 4] = ["look", "smell", "taste", "post_melt"];
 
 #[cw_serde]
 pub struct Entry {
@@ -27,13 +41,466 @@ pub struct Entry {
     pub genetics: String,
     pub farmer: String,
 }
-pub const ENTRY_ID: Item<u8> = Item::new("entry_id");
+// Entry IDs are shared by every event
+pub const ENTRY_ID: Item<u64> = Item::new("entry_id");
+
+#[cw_serde]
+pub struct EventInfo {
+    pub name: String,
+    pub date: Option<String>,
+    pub location: Option<String>,
+}
+
+// Event IDs -> Event Info
+pub const EVENTS: Map<u64, EventInfo> = Map::new("events");
+pub const EVENT_ID: Item<u64> = Item::new("event_id");
+
+// Event IDs -> Vector of category names
+pub const CATEGORIES: Map<u64, Vec<String>> = Map::new("categories");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct VotingWindow {
+    pub opens: Option<Scheduled>,
+    pub closes: Option<Expiration>,
+}
+
+impl VotingWindow {
+    /// Returns true once the window has opened, or if it has no opening time
+    pub fn has_opened(&self, block: &BlockInfo) -> bool {
+        self.opens
+            .as_ref()
+            .is_none_or(|opens| opens.is_triggered(block))
+    }
+
+    /// Returns true once the window has closed, never if it has no closing time
+    pub fn has_closed(&self, block: &BlockInfo) -> bool {
+        self.closes
+            .as_ref()
+            .is_some_and(|closes| closes.is_expired(block))
+    }
+}
+
+// Event IDs -> Event wide voting window, applies to every category of the event
+pub const VOTING_WINDOW: Map<u64, VotingWindow> = Map::new("voting_window");
+
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum ScoringMode {
+    /// Mean of every ballot
+    #[default]
+    Mean,
+    /// Mean of the ballots left after dropping the `trim` highest and lowest ballots of
+    /// each criterion, whatever their weight, entries with too few ballots to trim fall
+    /// back to the mean
+    TrimmedMean { trim: u32 },
+    /// Median of every ballot, stored once computed after voting has closed
+    Median,
+}
+
+/// Returns the median of the scores every ballot gave to a single criterion along with the
+/// weight of each ballot, as if every score was given as many times as its weight. Zero
+/// without weighted scores
+pub fn median(mut scores: Vec<(Uint128, u64)>) -> Decimal {
+    let weight: u64 = scores.iter().map(|(_, weight)| weight).sum();
+    if weight == 0 {
+        return Decimal::zero();
+    };
+    scores.sort();
+
+    if weight % 2 == 1 {
+        Decimal::from_ratio(nth(&scores, weight / 2), 1u128)
+    } else {
+        Decimal::from_ratio(
+            nth(&scores, weight / 2 - 1) + nth(&scores, weight / 2),
+            2u128,
+        )
+    }
+}
+
+/// Returns the sorted score covering the given unit of weight
+fn nth(scores: &[(Uint128, u64)], index: u64) -> Uint128 {
+    let mut position = 0;
+    for (score, weight) in scores {
+        position += weight;
+        if index < position {
+            return *score;
+        };
+    }
+    Uint128::zero()
+}
+
+#[cw_serde]
+pub struct CategoryInfo {
+    pub event: u64,
+    pub name: String,
+    pub criteria: Vec<String>,
+    // Criterion Name -> Weight
+    pub weights: BTreeMap<String, Decimal>,
+    pub voting_window: VotingWindow,
+    /// Overrides the quorum of the config for this category
+    pub min_ballots: Option<u64>,
+    pub scoring: ScoringMode,
+    /// Criteria that break ties in the rankings in order of priority, before the number
+    /// of ballots and the earliest submission
+    pub tie_breakers: Vec<String>,
+}
+
+impl CategoryInfo {
+    /// Returns whether the entry received enough ballots to be ranked
+    pub fn is_eligible(&self, config: &Config, totals: &EntryTotals) -> bool {
+        totals.count >= self.min_ballots.unwrap_or(config.min_ballots)
+    }
+
+    /// Returns the weight of a criterion, 1 unless set
+    pub fn criterion_weight(&self, criterion: &str) -> Decimal {
+        self.weights
+            .get(criterion)
+            .copied()
+            .unwrap_or(Decimal::one())
+    }
+
+    /// Returns the sum of every score multiplied by the weight of its criterion
+    pub fn weighted_sum(&self, votes: &Votes) -> Decimal {
+        votes
+            .iter()
+            .map(|(criterion, score)| {
+                Decimal::from_ratio(*score, 1u128) * self.criterion_weight(criterion)
+            })
+            .sum()
+    }
+
+    /// Returns the sum of the criterion scores multiplied by the weight of their criterion
+    pub fn weighted_score(&self, scores: &BTreeMap<String, Decimal>) -> Decimal {
+        scores
+            .iter()
+            .map(|(criterion, score)| *score * self.criterion_weight(criterion))
+            .sum()
+    }
+}
+
+// (Event IDs, Category Name) -> Category Info
+pub const CATEGORY_INFO: Map<(u64, String), CategoryInfo> = Map::new("category_info");
+
+// (Event IDs, Category Names, Entry IDs) -> Entry
+pub const CATEGORY_ENTRIES: Map<(u64, String, u64), Entry> = Map::new("category_entries");
+
+// Entry IDs -> (Event IDs, Category Names)
+pub const ENTRY_CATEGORY: Map<u64, (u64, String)> = Map::new("entry_category");
+
+// Entry IDs -> Block height the entry was added at
+pub const ENTRY_SUBMITTED: Map<u64, u64> = Map::new("entry_submitted");
+
+// (Event IDs, Entry IDs, Maker Addr) -> Votes
+pub const ENTRY_VOTES: Map<(u64, u64, Addr), Votes> = Map::new("entry_votes");
+
+// (Event IDs, Entry IDs, Maker Addr) -> Weight of the judge when the ballot was cast
+pub const BALLOT_WEIGHTS: Map<(u64, u64, Addr), u64> = Map::new("ballot_weights");
+
+#[cw_serde]
+pub struct VoteRecord {
+    pub votes: Votes,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+// (Entry IDs, Maker Addr, Sequence) -> Ballot, every submission of a judge starting at 0
+pub const VOTE_HISTORY: Map<(u64, Addr, u32), VoteRecord> = Map::new("vote_history");
+
+// Sums count the scores of every ballot as many times as the weight of its judge
+#[cw_serde]
+#[derive(Default)]
+pub struct EntryTotals {
+    /// Number of ballots
+    pub count: u64,
+    /// Sum of the weights of every ballot
+    pub weight: u64,
+    pub sums: Votes,
+    pub total: Uint128,
+    pub weighted_total: Decimal,
+}
+
+impl EntryTotals {
+    /// Returns the weighted mean of the vote sums, zero without votes
+    pub fn average(&self) -> Decimal {
+        if self.weight == 0 {
+            return Decimal::zero();
+        };
+        Decimal::from_ratio(self.total, self.weight)
+    }
+
+    /// Returns the weighted mean of the vote sums with every score multiplied by the
+    /// weight of its criterion, zero without votes
+    pub fn weighted_average(&self) -> Decimal {
+        if self.weight == 0 {
+            return Decimal::zero();
+        };
+        self.weighted_total / Decimal::from_ratio(self.weight, 1u128)
+    }
+
+    /// Returns the weighted mean score of a single criterion, zero without votes
+    pub fn criterion_average(&self, criterion: &str) -> Decimal {
+        match self.sums.get(criterion) {
+            Some(sum) if self.weight > 0 => Decimal::from_ratio(*sum, self.weight),
+            _ => Decimal::zero(),
+        }
+    }
+
+    pub fn add(&mut self, votes: &Votes, weighted_sum: Decimal, weight: u64) {
+        self.count += 1;
+        self.weight += weight;
+        for (criterion, score) in votes {
+            let score = *score * Uint128::from(weight);
+            *self.sums.entry(criterion.clone()).or_default() += score;
+            self.total += score;
+        }
+        self.weighted_total += weighted_sum * Decimal::from_ratio(weight, 1u128);
+    }
+
+    pub fn remove(&mut self, votes: &Votes, weighted_sum: Decimal, weight: u64) {
+        self.count -= 1;
+        self.weight -= weight;
+        for (criterion, score) in votes {
+            let score = *score * Uint128::from(weight);
+            if let Some(sum) = self.sums.get_mut(criterion) {
+                *sum -= score;
+            };
+            self.total -= score;
+        }
+        self.weighted_total -= weighted_sum * Decimal::from_ratio(weight, 1u128);
+    }
+}
+
+// Entry IDs -> Totals of every vote, kept up to date as votes are cast
+pub const ENTRY_TOTALS: Map<u64, EntryTotals> = Map::new("entry_totals");
 
-// Vector of category names
-pub const CATEGORIES: Item<Vec<String>> = Item::new("categories");
+// ((Entry IDs, Criterion Name), Score, Maker Addr) -> Weight of the ballot, kept for
+// categories scored by trimmed mean so the ballots to drop are read in score order
+pub const SORTED_SCORES: Map<((u64, String), u128, Addr), u64> = Map::new("sorted_scores");
 
-// (Category Names, Entry IDs) -> Entry
-pub const CATEGORY_ENTRIES: Map<(String, u8), Entry> = Map::new("category_entries");
+// Entry IDs -> Criterion Name -> Median, stored for median scored categories once computed
+pub const ENTRY_MEDIANS: Map<u64, BTreeMap<String, Decimal>> = Map::new("entry_medians");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ScoreStats {
+    pub count: u64,
+    pub sum: Uint128,
+    pub sum_squares: Uint128,
+}
+
+impl ScoreStats {
+    pub fn add(&mut self, score: Uint128) {
+        self.count += 1;
+        self.sum += score;
+        self.sum_squares += score * score;
+    }
+
+    pub fn mean(&self) -> Decimal {
+        if self.count == 0 {
+            return Decimal::zero();
+        };
+        Decimal::from_ratio(self.sum, self.count)
+    }
+
+    /// Returns the population standard deviation of the scores
+    pub fn std_dev(&self) -> Decimal {
+        if self.count == 0 {
+            return Decimal::zero();
+        };
+        let count = Uint128::from(self.count);
+        let variance = Decimal::from_ratio(
+            count * self.sum_squares - self.sum * self.sum,
+            count * count,
+        );
+        variance.sqrt()
+    }
+
+    /// Rescales a score given by this judge to the mean and spread of every score,
+    /// scores that would fall below zero are capped at zero
+    pub fn normalize(&self, score: Uint128, all: &ScoreStats) -> Decimal {
+        let mean = self.mean();
+        let std_dev = self.std_dev();
+        // Judges that gave every score the same value are moved to the overall mean
+        if std_dev.is_zero() {
+            return all.mean();
+        };
+
+        let score = Decimal::from_ratio(score, 1u128);
+        if score >= mean {
+            all.mean() + (score - mean) * all.std_dev() / std_dev
+        } else {
+            all.mean()
+                .saturating_sub((mean - score) * all.std_dev() / std_dev)
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum NormalizationStage {
+    /// Collecting the score statistics of every judge
+    Stats,
+    /// Adding up the normalized ballots of every entry
+    Scores,
+    Done,
+}
+
+impl fmt::Display for NormalizationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NormalizationStage::Stats => write!(f, "stats"),
+            NormalizationStage::Scores => write!(f, "scores"),
+            NormalizationStage::Done => write!(f, "done"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Normalization {
+    pub stage: NormalizationStage,
+    /// Entry ID and judge of the last ballot processed in the current stage
+    pub cursor: Option<(u64, Addr)>,
+}
+
+// Event IDs -> Normalization of the judges of the event
+pub const NORMALIZATION: Map<u64, Normalization> = Map::new("normalization");
+
+// (Event IDs, Maker Addr) -> Statistics of every score given by the judge in the event
+pub const JUDGE_STATS: Map<(u64, Addr), ScoreStats> = Map::new("judge_stats");
+
+// Event IDs -> Statistics of every score given by any judge in the event
+pub const GLOBAL_STATS: Map<u64, ScoreStats> = Map::new("global_stats");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct NormalizedTotals {
+    pub count: u64,
+    pub weight: u64,
+    // Criterion Name -> Sum of the normalized scores multiplied by the weight of each ballot
+    pub sums: BTreeMap<String, Decimal>,
+}
+
+impl NormalizedTotals {
+    /// Returns the weighted mean of the normalized vote sums, zero without votes
+    pub fn average(&self) -> Decimal {
+        if self.weight == 0 {
+            return Decimal::zero();
+        };
+        let total: Decimal = self.sums.values().copied().sum();
+        total / Decimal::from_ratio(self.weight, 1u128)
+    }
+
+    pub fn add(&mut self, votes: BTreeMap<String, Decimal>, weight: u64) {
+        self.count += 1;
+        self.weight += weight;
+        for (criterion, score) in votes {
+            *self.sums.entry(criterion).or_default() += score * Decimal::from_ratio(weight, 1u128);
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum RankBy {
+    /// Sum of every score of every vote multiplied by the weight of its judge
+    Total,
+    /// Sum of every score of every vote multiplied by the weight of its criterion and
+    /// of its judge
+    WeightedTotal,
+    /// Sum of the criterion scores under the scoring mode of the category multiplied by
+    /// the weight of their criterion. Means come from the totals, trimmed means from the
+    /// totals less the dropped ballots and medians from the stored medians, which count
+    /// as zero until computed
+    #[default]
+    Average,
+    /// Mean of the vote sums after normalizing the scores of every judge, zero until
+    /// normalization is done
+    Normalized,
+}
+
+#[cw_serde]
+pub struct Placement {
+    /// Entries that are still level after every tie-break share a rank
+    pub rank: u64,
+    pub entry_id: u64,
+    pub name: String,
+    pub score: Decimal,
+    pub count: u64,
+    pub eligible: bool,
+    pub tied: bool,
+}
+
+#[cw_serde]
+pub struct CategoryResults {
+    /// Every entry of the category from the first place to the last
+    pub placements: Vec<Placement>,
+    /// Ranking the placements follow
+    pub rank_by: RankBy,
+    pub finalized_at_height: u64,
+    pub finalized_at_time: Timestamp,
+}
+
+// (Event IDs, Category Name) -> Final results, stored once voting is closed
+pub const RESULTS: Map<(u64, String), CategoryResults> = Map::new("results");
+
+// Entry IDs -> Totals of the normalized ballots, complete once normalization is done
+pub const NORMALIZED_TOTALS: Map<u64, NormalizedTotals> = Map::new("normalized_totals");
+
+// (Entry IDs, Maker Addr) -> sha256(votes || salt), removed once revealed
+pub const COMMITMENTS: Map<(u64, Addr), Binary> = Map::new("commitments");
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum Phase {
+    Registration,
+    Voting,
+    /// Only used when ballots are committed during voting and revealed afterwards
+    Reveal,
+    Closed,
+    Finalized,
+}
+
+impl Phase {
+    /// Returns the phase that follows this one, if any
+    pub fn next(&self, commit_reveal: bool) -> Option<Phase> {
+        match self {
+            Phase::Registration => Some(Phase::Voting),
+            Phase::Voting if commit_reveal => Some(Phase::Reveal),
+            Phase::Voting | Phase::Reveal => Some(Phase::Closed),
+            Phase::Closed => Some(Phase::Finalized),
+            Phase::Finalized => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Registration => write!(f, "registration"),
+            Phase::Voting => write!(f, "voting"),
+            Phase::Reveal => write!(f, "reveal"),
+            Phase::Closed => write!(f, "closed"),
+            Phase::Finalized => write!(f, "finalized"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PhaseInfo {
+    pub phase: Phase,
+    pub changed_at_height: u64,
+    pub changed_at_time: Timestamp,
+}
+// Event IDs -> Current phase of the event
+pub const PHASE: Map<u64, PhaseInfo> = Map::new("phase");
+
+#[cw_serde]
+pub struct MakersSnapshot {
+    /// Makers group of the config when voting opened, kept if the group is rotated later
+    pub makers_cw4_group: Addr,
+    pub height: u64,
+}
 
-// (Entry IDs, Maker Addr) -> Votes
-pub const ENTRY_VOTES: Map<(u8, Addr), Votes> = Map::new("entry_votes");
+// Event IDs -> Makers group and block height judges are read at, recorded when voting opens
+pub const MAKERS_SNAPSHOT: Map<u64, MakersSnapshot> = Map::new("makers_snapshot");
@@ -1,22 +1,149 @@
 use crate::{
-    msg::{EntriesResponse, ExecuteMsg, InstantiateMsg, QueryMsg},
-    state::{Entry, Votes},
+    msg::{
+        Ballot, EntriesResponse, EntryScoresResponse, EventResponse, ExecuteMsg, InstantiateMsg,
+        LeaderboardResponse, MigrateMsg, QueryMsg, ResultsResponse, TallyVotesResponse,
+    },
+    state::{
+        CategoryInfo, Config, Entry, EntryTotals, EventInfo, MakersSnapshot, Normalization, Phase,
+        PhaseInfo, Placement, RankBy, ScoreStats, ScoringMode, VoteRecord, Votes, VotingWindow,
+    },
     ContractError,
 };
-use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{from_slice, to_vec, Addr, Binary, Coin, Decimal, Empty, Uint128};
 use cw4::Member;
-use cw4_group::msg::InstantiateMsg as Cw4InstantiateMsg;
-use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+use cw4_group::msg::{ExecuteMsg as Cw4ExecuteMsg, InstantiateMsg as Cw4InstantiateMsg};
+use cw_multi_test::{next_block, App, AppBuilder, Contract, ContractWrapper, Executor};
+use cw_utils::{Expiration, Scheduled};
+use sha2::{Digest, Sha256};
 
 pub fn voting_contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         crate::contract::execute,
         crate::contract::instantiate,
         crate::contract::query,
+    )
+    .with_migrate(crate::contract::migrate);
+    Box::new(contract)
+}
+
+/// Voting contract that instantiates straight into a populated version 0.1.0 state
+pub fn legacy_voting_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        legacy::instantiate,
+        crate::contract::query,
     );
     Box::new(contract)
 }
 
+/// Voting contract that claims to be a newer version than this one
+pub fn newer_voting_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        legacy::instantiate_newer,
+        crate::contract::query,
+    );
+    Box::new(contract)
+}
+
+mod legacy {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Uint128};
+    use cw_storage_plus::{Item, Map};
+
+    use crate::state::Entry;
+    use crate::ContractError;
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admins_cw4_group: String,
+        pub makers_cw4_group: String,
+        pub with_votes: bool,
+    }
+
+    #[cw_serde]
+    struct Config {
+        admins_cw4_group: Addr,
+        makers_cw4_group: Addr,
+    }
+
+    #[cw_serde]
+    struct Votes {
+        look: Uint128,
+        smell: Uint128,
+        taste: Uint128,
+        post_melt: Uint128,
+    }
+
+    const CONFIG: Item<Config> = Item::new("config");
+    const ENTRY_ID: Item<u8> = Item::new("entry_id");
+    const CATEGORIES: Item<Vec<String>> = Item::new("categories");
+    const CATEGORY_ENTRIES: Map<(String, u8), Entry> = Map::new("category_entries");
+    const ENTRY_VOTES: Map<(u8, Addr), Votes> = Map::new("entry_votes");
+
+    pub fn instantiate_newer(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: crate::msg::InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        let res = crate::contract::instantiate(deps.branch(), env, info, msg)?;
+        cw2::set_contract_version(deps.storage, "crates.io:voting-contract", "99.0.0")?;
+        Ok(res)
+    }
+
+    /// Writes the state of an event that has used up every u8 entry ID
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        cw2::set_contract_version(deps.storage, "crates.io:voting-contract", "0.1.0")?;
+
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                admins_cw4_group: Addr::unchecked(msg.admins_cw4_group),
+                makers_cw4_group: Addr::unchecked(msg.makers_cw4_group),
+            },
+        )?;
+        CATEGORIES.save(deps.storage, &vec!["category_1".to_string()])?;
+        ENTRY_ID.save(deps.storage, &255)?;
+
+        for (entry_id, maker_addr) in [(254, super::FIRST_MAKER), (255, super::SECOND_MAKER)] {
+            let entry = Entry {
+                name: "entry_name".to_string(),
+                category: "category_1".to_string(),
+                maker_addr: Addr::unchecked(maker_addr),
+                maker_name: "maker_name".to_string(),
+                breeder: "entry_breeder".to_string(),
+                genetics: "entry_genetics".to_string(),
+                farmer: "entry_farmer".to_string(),
+            };
+            CATEGORY_ENTRIES.save(deps.storage, ("category_1".to_string(), entry_id), &entry)?;
+        }
+
+        if msg.with_votes {
+            let votes = Votes {
+                look: Uint128::new(775),
+                smell: Uint128::new(820),
+                taste: Uint128::new(1000),
+                post_melt: Uint128::new(250),
+            };
+            ENTRY_VOTES.save(
+                deps.storage,
+                (254, Addr::unchecked(super::SECOND_MAKER)),
+                &votes,
+            )?;
+        }
+
+        Ok(Response::new())
+    }
+}
+
 pub fn cw4_group_contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
         cw4_group::contract::execute,
@@ -31,6 +158,9 @@ const ADMIN: &str = "juno..admin";
 const FIRST_MAKER: &str = "juno..firstmaker";
 const SECOND_MAKER: &str = "juno..secondmaker";
 
+// Event created along with every contract, the same ID the legacy event migrates to
+const EVENT: u64 = 1;
+
 fn mock_app() -> App {
     AppBuilder::new().build(|router, _, storage| {
         router
@@ -48,54 +178,166 @@ fn mock_app() -> App {
 }
 
 fn setup_contract(app: &mut App, admins_cw4_group: String, makers_cw4_group: String) -> Addr {
+    setup_contract_with_msg(
+        app,
+        InstantiateMsg {
+            admins_cw4_group,
+            makers_cw4_group,
+            min_score: None,
+            max_score: None,
+            score_step: None,
+            commit_reveal: None,
+            min_ballots: None,
+            max_amendments: None,
+            equal_weights: None,
+        },
+    )
+}
+
+fn setup_contract_with_msg(app: &mut App, msg: InstantiateMsg) -> Addr {
     let code_id = app.store_code(voting_contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(ADMIN),
+            &msg,
+            &[],
+            "Voting Contract",
+            None,
+        )
+        .unwrap();
+
+    setup_event(app, contract_addr.clone(), "Legends 2023".to_string());
+
+    contract_addr
+}
+
+fn setup_event(app: &mut App, contract_addr: Addr, name: String) {
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr,
+        &ExecuteMsg::CreateEvent {
+            name,
+            date: None,
+            location: None,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn setup_legacy_contract(app: &mut App, with_votes: bool) -> Addr {
+    let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+    let makers_cw4_group = setup_cw4_group(app, vec![FIRST_MAKER, SECOND_MAKER]);
+    let code_id = app.store_code(legacy_voting_contract());
     app.instantiate_contract(
         code_id,
         Addr::unchecked(ADMIN),
-        &InstantiateMsg {
-            admins_cw4_group,
-            makers_cw4_group,
+        &legacy::InstantiateMsg {
+            admins_cw4_group: admin_cw4_group.to_string(),
+            makers_cw4_group: makers_cw4_group.to_string(),
+            with_votes,
         },
-        &vec![],
+        &[],
         "Voting Contract",
-        None,
+        Some(ADMIN.to_string()),
     )
     .unwrap()
 }
 
+fn migrate_contract(app: &mut App, contract_addr: Addr) {
+    let code_id = app.store_code(voting_contract());
+    app.migrate_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr,
+        &MigrateMsg {},
+        code_id,
+    )
+    .unwrap();
+}
+
 fn setup_cw4_group(app: &mut App, members: Vec<&str>) -> Addr {
+    setup_weighted_cw4_group(app, members.into_iter().map(|m| (m, 1)).collect())
+}
+
+fn setup_weighted_cw4_group(app: &mut App, members: Vec<(&str, u64)>) -> Addr {
     let code_id = app.store_code(cw4_group_contract());
     let members = members
         .iter()
-        .map(|m| Member {
+        .map(|(m, weight)| Member {
             addr: m.to_string(),
-            weight: 1,
+            weight: *weight,
         })
         .collect();
-    app.instantiate_contract(
-        code_id,
+    let group_addr = app
+        .instantiate_contract(
+            code_id,
+            Addr::unchecked(ADMIN),
+            &Cw4InstantiateMsg {
+                admin: Some(ADMIN.to_string()),
+                members,
+            },
+            &[],
+            "CW4 Group",
+            None,
+        )
+        .unwrap();
+    // Membership snapshots only see changes from previous blocks
+    app.update_block(next_block);
+    group_addr
+}
+
+fn setup_category(app: &mut App, contract_addr: Addr, category: String) {
+    app.execute_contract(
         Addr::unchecked(ADMIN),
-        &Cw4InstantiateMsg {
-            admin: Some(ADMIN.to_string()),
-            members,
+        contract_addr,
+        &ExecuteMsg::AddCategory {
+            event: EVENT,
+            category,
+            criteria: None,
+            weights: None,
+            min_ballots: None,
+            scoring: None,
+            tie_breakers: None,
         },
-        &vec![],
-        "CW4 Group",
-        None,
+        &[],
     )
-    .unwrap()
+    .unwrap();
 }
 
-fn setup_category(app: &mut App, contract_addr: Addr, category: String) {
+fn setup_custom_category(
+    app: &mut App,
+    contract_addr: Addr,
+    category: String,
+    criteria: Vec<&str>,
+) {
     app.execute_contract(
         Addr::unchecked(ADMIN),
         contract_addr,
-        &ExecuteMsg::AddCategory { category },
-        &vec![],
+        &ExecuteMsg::AddCategory {
+            event: EVENT,
+            category,
+            criteria: Some(criteria.iter().map(|c| c.to_string()).collect()),
+            weights: None,
+            min_ballots: None,
+            scoring: None,
+            tie_breakers: None,
+        },
+        &[],
     )
     .unwrap();
 }
 
+fn default_votes(look: u128, smell: u128, taste: u128, post_melt: u128) -> Votes {
+    Votes::from([
+        ("look".to_string(), Uint128::new(look)),
+        ("smell".to_string(), Uint128::new(smell)),
+        ("taste".to_string(), Uint128::new(taste)),
+        ("post_melt".to_string(), Uint128::new(post_melt)),
+    ])
+}
+
+#[allow(clippy::too_many_arguments)]
 fn setup_entry(
     app: &mut App,
     contract_addr: Addr,
@@ -111,6 +353,7 @@ fn setup_entry(
         Addr::unchecked(ADMIN),
         contract_addr,
         &ExecuteMsg::AddEntry {
+            event: EVENT,
             name,
             category,
             maker_addr,
@@ -119,7 +362,60 @@ fn setup_entry(
             genetics,
             farmer,
         },
-        &vec![],
+        &[],
+    )
+    .unwrap();
+}
+
+fn setup_commit_reveal_contract(app: &mut App) -> Addr {
+    let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+    let makers_cw4_group = setup_cw4_group(app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+    let contract_addr = setup_contract_with_msg(
+        app,
+        InstantiateMsg {
+            admins_cw4_group: admin_cw4_group.to_string(),
+            makers_cw4_group: makers_cw4_group.to_string(),
+            min_score: None,
+            max_score: None,
+            score_step: None,
+            commit_reveal: Some(true),
+            min_ballots: None,
+            max_amendments: None,
+            equal_weights: None,
+        },
+    );
+
+    setup_category(app, contract_addr.clone(), "category_1".to_string());
+    setup_entry(
+        app,
+        contract_addr.clone(),
+        "entry_name".to_string(),
+        "category_1".to_string(),
+        FIRST_MAKER.to_string(),
+        "maker_name".to_string(),
+        "entry_breeder".to_string(),
+        "entry_genetics".to_string(),
+        "entry_farmer".to_string(),
+    );
+    setup_phase(app, contract_addr.clone(), Phase::Voting);
+
+    contract_addr
+}
+
+fn commitment(votes: &Votes, salt: &str) -> Binary {
+    let preimage = [to_vec(votes).unwrap(), salt.as_bytes().to_vec()].concat();
+    Binary::from(Sha256::digest(preimage).as_slice())
+}
+
+fn setup_phase(app: &mut App, contract_addr: Addr, phase: Phase) {
+    app.execute_contract(
+        Addr::unchecked(ADMIN),
+        contract_addr,
+        &ExecuteMsg::SetPhase {
+            event: EVENT,
+            phase,
+        },
+        &[],
     )
     .unwrap();
 }
@@ -129,26 +425,67 @@ fn setup_vote(
     contract_addr: Addr,
     sender: &str,
     category: String,
-    entry_id: u8,
+    entry_id: u64,
     votes: Votes,
 ) {
     app.execute_contract(
         Addr::unchecked(sender),
         contract_addr,
         &ExecuteMsg::Vote {
-            category,
+            event: EVENT,
+            category: Some(category),
             entry_id,
             votes,
         },
-        &vec![],
+        &[],
     )
     .unwrap();
 }
 
+mod instantiate {
+    use super::*;
+
+    #[test]
+    fn test_invalid_score_range() {
+        let mut app = mock_app();
+        let code_id = app.store_code(voting_contract());
+
+        for (min_score, max_score, score_step) in [
+            (Some(Uint128::new(1000)), Some(Uint128::new(100)), None),
+            (None, None, Some(Uint128::zero())),
+        ] {
+            let err = app
+                .instantiate_contract(
+                    code_id,
+                    Addr::unchecked(ADMIN),
+                    &InstantiateMsg {
+                        admins_cw4_group: "admins_address".to_string(),
+                        makers_cw4_group: "makers_address".to_string(),
+                        min_score,
+                        max_score,
+                        score_step,
+                        commit_reveal: None,
+                        min_ballots: None,
+                        max_amendments: None,
+                        equal_weights: None,
+                    },
+                    &[],
+                    "Voting Contract",
+                    None,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidScoreRange {}.to_string()
+            );
+        }
+    }
+}
+
 mod execute {
     use super::*;
 
-    mod add_category {
+    mod create_event {
         use super::*;
 
         #[test]
@@ -161,21 +498,67 @@ mod execute {
                 "contract_address".to_string(),
             );
 
-            app.execute_contract(
-                Addr::unchecked(ADMIN),
-                contract_addr.clone(),
-                &ExecuteMsg::AddCategory {
-                    category: String::from("category_1"),
-                },
-                &vec![],
-            )
-            .unwrap();
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::CreateEvent {
+                        name: "Legends 2024".to_string(),
+                        date: Some("2024-11-30".to_string()),
+                        location: Some("Denver".to_string()),
+                    },
+                    &[],
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "event_id" && attr.value == "2"));
+
+            let res: Vec<EventResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Events {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                vec![
+                    EventResponse {
+                        id: EVENT,
+                        data: EventInfo {
+                            name: "Legends 2023".to_string(),
+                            date: None,
+                            location: None,
+                        },
+                        phase: Phase::Registration,
+                        makers_snapshot: None,
+                    },
+                    EventResponse {
+                        id: 2,
+                        data: EventInfo {
+                            name: "Legends 2024".to_string(),
+                            date: Some("2024-11-30".to_string()),
+                            location: Some("Denver".to_string()),
+                        },
+                        phase: Phase::Registration,
+                        makers_snapshot: None,
+                    },
+                ]
+            );
 
+            // Categories belong to a single event
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
             let res: Vec<String> = app
                 .wrap()
-                .query_wasm_smart(contract_addr, &QueryMsg::Categories {})
+                .query_wasm_smart(contract_addr, &QueryMsg::Categories { event: 2 })
                 .unwrap();
-            assert_eq!(res, vec![String::from("category_1")]);
+            assert!(res.is_empty());
         }
 
         #[test]
@@ -192,10 +575,12 @@ mod execute {
                 .execute_contract(
                     Addr::unchecked(USER),
                     contract_addr.clone(),
-                    &ExecuteMsg::AddCategory {
-                        category: String::from("category_1"),
+                    &ExecuteMsg::CreateEvent {
+                        name: "Legends 2024".to_string(),
+                        date: None,
+                        location: None,
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
@@ -205,7 +590,7 @@ mod execute {
         }
 
         #[test]
-        fn test_invalid_category() {
+        fn test_invalid_event() {
             let mut app = mock_app();
             let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
             let contract_addr = setup_contract(
@@ -214,34 +599,30 @@ mod execute {
                 "contract_address".to_string(),
             );
 
-            app.execute_contract(
-                Addr::unchecked(ADMIN),
-                contract_addr.clone(),
-                &ExecuteMsg::AddCategory {
-                    category: String::from("category_1"),
-                },
-                &vec![],
-            )
-            .unwrap();
-
             let err = app
                 .execute_contract(
                     Addr::unchecked(ADMIN),
-                    contract_addr.clone(),
+                    contract_addr,
                     &ExecuteMsg::AddCategory {
-                        category: String::from("category_1"),
+                        event: 2,
+                        category: "category_1".to_string(),
+                        criteria: None,
+                        weights: None,
+                        min_ballots: None,
+                        scoring: None,
+                        tie_breakers: None,
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
                 err.source().unwrap().to_string(),
-                ContractError::InvalidCategory {}.to_string()
+                ContractError::InvalidEvent {}.to_string()
             );
         }
     }
 
-    mod add_entry {
+    mod add_category {
         use super::*;
 
         #[test]
@@ -254,54 +635,31 @@ mod execute {
                 "contract_address".to_string(),
             );
 
-            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
-
             app.execute_contract(
                 Addr::unchecked(ADMIN),
                 contract_addr.clone(),
-                &ExecuteMsg::AddEntry {
-                    name: "entry_name".to_string(),
-                    category: "category_1".to_string(),
-                    maker_addr: FIRST_MAKER.to_string(),
-                    maker_name: "maker_name".to_string(),
-                    breeder: "entry_breeder".to_string(),
-                    genetics: "entry_genetics".to_string(),
-                    farmer: "entry_farmer".to_string(),
+                &ExecuteMsg::AddCategory {
+                    event: EVENT,
+                    category: String::from("category_1"),
+                    criteria: None,
+                    weights: None,
+                    min_ballots: None,
+                    scoring: None,
+                    tie_breakers: None,
                 },
-                &vec![],
+                &[],
             )
             .unwrap();
 
-            let res: Vec<EntriesResponse> = app
+            let res: Vec<String> = app
                 .wrap()
-                .query_wasm_smart(
-                    contract_addr,
-                    &QueryMsg::Entries {
-                        category: "category_1".to_string(),
-                        start_after: None,
-                        limit: None,
-                    },
-                )
+                .query_wasm_smart(contract_addr, &QueryMsg::Categories { event: EVENT })
                 .unwrap();
-            assert_eq!(
-                res,
-                vec![EntriesResponse {
-                    id: 1,
-                    data: Entry {
-                        name: "entry_name".to_string(),
-                        category: "category_1".to_string(),
-                        maker_addr: Addr::unchecked(FIRST_MAKER.to_string()),
-                        maker_name: "maker_name".to_string(),
-                        breeder: "entry_breeder".to_string(),
-                        genetics: "entry_genetics".to_string(),
-                        farmer: "entry_farmer".to_string(),
-                    }
-                }]
-            );
+            assert_eq!(res, vec![String::from("category_1")]);
         }
 
         #[test]
-        fn test_invalid_category() {
+        fn test_invalid_admin() {
             let mut app = mock_app();
             let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
             let contract_addr = setup_contract(
@@ -312,28 +670,28 @@ mod execute {
 
             let err = app
                 .execute_contract(
-                    Addr::unchecked(ADMIN),
+                    Addr::unchecked(USER),
                     contract_addr.clone(),
-                    &ExecuteMsg::AddEntry {
-                        name: "entry_name".to_string(),
-                        category: "category_1".to_string(),
-                        maker_addr: FIRST_MAKER.to_string(),
-                        maker_name: "maker_name".to_string(),
-                        breeder: "entry_breeder".to_string(),
-                        genetics: "entry_genetics".to_string(),
-                        farmer: "entry_farmer".to_string(),
+                    &ExecuteMsg::AddCategory {
+                        event: EVENT,
+                        category: String::from("category_1"),
+                        criteria: None,
+                        weights: None,
+                        min_ballots: None,
+                        scoring: None,
+                        tie_breakers: None,
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
                 err.source().unwrap().to_string(),
-                ContractError::InvalidCategory {}.to_string()
+                ContractError::Unauthorized {}.to_string()
             );
         }
 
         #[test]
-        fn test_invalid_admin() {
+        fn test_invalid_category() {
             let mut app = mock_app();
             let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
             let contract_addr = setup_contract(
@@ -342,13 +700,331 @@ mod execute {
                 "contract_address".to_string(),
             );
 
-            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::AddCategory {
+                    event: EVENT,
+                    category: String::from("category_1"),
+                    criteria: None,
+                    weights: None,
+                    min_ballots: None,
+                    scoring: None,
+                    tie_breakers: None,
+                },
+                &[],
+            )
+            .unwrap();
 
             let err = app
                 .execute_contract(
-                    Addr::unchecked(USER),
+                    Addr::unchecked(ADMIN),
                     contract_addr.clone(),
-                    &ExecuteMsg::AddEntry {
+                    &ExecuteMsg::AddCategory {
+                        event: EVENT,
+                        category: String::from("category_1"),
+                        criteria: None,
+                        weights: None,
+                        min_ballots: None,
+                        scoring: None,
+                        tie_breakers: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidCategory {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddCategory {
+                        event: EVENT,
+                        category: String::from("category_1"),
+                        criteria: None,
+                        weights: None,
+                        min_ballots: None,
+                        scoring: None,
+                        tie_breakers: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Registration,
+                    current: Phase::Voting
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn test_custom_criteria() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_custom_category(
+                &mut app,
+                contract_addr.clone(),
+                "edibles".to_string(),
+                vec!["texture", "dosing_accuracy", "taste"],
+            );
+
+            let res: CategoryInfo = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::CategoryInfo {
+                        event: EVENT,
+                        category: "edibles".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res.criteria,
+                vec![
+                    "texture".to_string(),
+                    "dosing_accuracy".to_string(),
+                    "taste".to_string()
+                ]
+            );
+
+            for criteria in [
+                vec![],
+                vec!["taste".to_string(), "taste".to_string()],
+                vec!["".to_string()],
+            ] {
+                let err = app
+                    .execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::AddCategory {
+                            event: EVENT,
+                            category: "concentrates".to_string(),
+                            criteria: Some(criteria),
+                            weights: None,
+                            min_ballots: None,
+                            scoring: None,
+                            tie_breakers: None,
+                        },
+                        &[],
+                    )
+                    .unwrap_err();
+                assert_eq!(
+                    err.source().unwrap().to_string(),
+                    ContractError::InvalidCriteria {}.to_string()
+                );
+            }
+        }
+
+        #[test]
+        fn test_weights() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::AddCategory {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    criteria: None,
+                    weights: Some(BTreeMap::from([(
+                        "taste".to_string(),
+                        Decimal::percent(150),
+                    )])),
+                    min_ballots: None,
+                    scoring: None,
+                    tie_breakers: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: CategoryInfo = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::CategoryInfo {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.weights["look"], Decimal::one());
+            assert_eq!(res.weights["taste"], Decimal::percent(150));
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddCategory {
+                        event: EVENT,
+                        category: "category_2".to_string(),
+                        criteria: None,
+                        weights: Some(BTreeMap::from([(
+                            "texture".to_string(),
+                            Decimal::percent(150),
+                        )])),
+                        min_ballots: None,
+                        scoring: None,
+                        tie_breakers: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::UnknownCriterion {
+                    criterion: "texture".to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod add_entry {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::AddEntry {
+                    event: EVENT,
+                    name: "entry_name".to_string(),
+                    category: "category_1".to_string(),
+                    maker_addr: FIRST_MAKER.to_string(),
+                    maker_name: "maker_name".to_string(),
+                    breeder: "entry_breeder".to_string(),
+                    genetics: "entry_genetics".to_string(),
+                    farmer: "entry_farmer".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: Vec<EntriesResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::Entries {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                vec![EntriesResponse {
+                    id: 1,
+                    data: Entry {
+                        name: "entry_name".to_string(),
+                        category: "category_1".to_string(),
+                        maker_addr: Addr::unchecked(FIRST_MAKER.to_string()),
+                        maker_name: "maker_name".to_string(),
+                        breeder: "entry_breeder".to_string(),
+                        genetics: "entry_genetics".to_string(),
+                        farmer: "entry_farmer".to_string(),
+                    },
+                    ballots: 0,
+                    eligible: false,
+                }]
+            );
+        }
+
+        #[test]
+        fn test_invalid_category() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddEntry {
+                        event: EVENT,
+                        name: "entry_name".to_string(),
+                        category: "category_1".to_string(),
+                        maker_addr: FIRST_MAKER.to_string(),
+                        maker_name: "maker_name".to_string(),
+                        breeder: "entry_breeder".to_string(),
+                        genetics: "entry_genetics".to_string(),
+                        farmer: "entry_farmer".to_string(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidCategory {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_invalid_admin() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddEntry {
+                        event: EVENT,
                         name: "entry_name".to_string(),
                         category: "category_1".to_string(),
                         maker_addr: FIRST_MAKER.to_string(),
@@ -357,7 +1033,7 @@ mod execute {
                         genetics: "entry_genetics".to_string(),
                         farmer: "entry_farmer".to_string(),
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
@@ -365,6 +1041,46 @@ mod execute {
                 ContractError::Unauthorized {}.to_string()
             );
         }
+
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddEntry {
+                        event: EVENT,
+                        name: "entry_name".to_string(),
+                        category: "category_1".to_string(),
+                        maker_addr: FIRST_MAKER.to_string(),
+                        maker_name: "maker_name".to_string(),
+                        breeder: "entry_breeder".to_string(),
+                        genetics: "entry_genetics".to_string(),
+                        farmer: "entry_farmer".to_string(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Registration,
+                    current: Phase::Voting
+                }
+                .to_string()
+            );
+        }
     }
 
     mod vote {
@@ -393,23 +1109,20 @@ mod execute {
                 "entry_genetics".to_string(),
                 "entry_farmer".to_string(),
             );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
 
-            let votes = Votes {
-                look: Uint128::new(775),
-                smell: Uint128::new(820),
-                taste: Uint128::new(1000),
-                post_melt: Uint128::new(250),
-            };
+            let votes = default_votes(775, 820, 1000, 250);
 
             app.execute_contract(
                 Addr::unchecked(SECOND_MAKER),
                 contract_addr.clone(),
                 &ExecuteMsg::Vote {
-                    category: "category_1".to_string(),
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
                     entry_id: 1,
                     votes: votes.clone(),
                 },
-                &vec![],
+                &[],
             )
             .unwrap();
 
@@ -418,6 +1131,7 @@ mod execute {
                 .query_wasm_smart(
                     contract_addr,
                     &QueryMsg::Votes {
+                        event: EVENT,
                         entry_id: 1,
                         maker_addr: SECOND_MAKER.to_string(),
                     },
@@ -449,24 +1163,21 @@ mod execute {
                 "entry_genetics".to_string(),
                 "entry_farmer".to_string(),
             );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
 
-            let votes = Votes {
-                look: Uint128::new(775),
-                smell: Uint128::new(820),
-                taste: Uint128::new(1000),
-                post_melt: Uint128::new(250),
-            };
+            let votes = default_votes(775, 820, 1000, 250);
 
             let err = app
                 .execute_contract(
                     Addr::unchecked(FIRST_MAKER),
                     contract_addr.clone(),
                     &ExecuteMsg::Vote {
-                        category: "category_1".to_string(),
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
                         entry_id: 1,
                         votes: votes.clone(),
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
@@ -479,11 +1190,12 @@ mod execute {
                     Addr::unchecked(SECOND_MAKER),
                     contract_addr.clone(),
                     &ExecuteMsg::Vote {
-                        category: "category_1".to_string(),
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
                         entry_id: 1,
                         votes: votes.clone(),
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
@@ -493,13 +1205,193 @@ mod execute {
         }
 
         #[test]
-        fn test_invalid_category() {
+        fn test_amendments() {
             let mut app = mock_app();
             let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
             let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
-            let contract_addr = setup_contract(
+            let contract_addr = setup_contract_with_msg(
                 &mut app,
-                admin_cw4_group.to_string(),
+                InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: makers_cw4_group.to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: None,
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: Some(1),
+                    equal_weights: None,
+                },
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(775, 820, 1000, 250),
+            );
+            let first_block = app.block_info();
+
+            app.update_block(|block| {
+                block.height += 5;
+                block.time = block.time.plus_seconds(30);
+            });
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(800, 820, 900, 250),
+            );
+            let second_block = app.block_info();
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: None,
+                        entry_id: 1,
+                        votes: default_votes(100, 100, 100, 100),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::TooManyAmendments { max: 1 }.to_string()
+            );
+
+            // The original ballot is kept next to the amendment that replaced it
+            let res: Vec<VoteRecord> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::VoteHistory {
+                        event: EVENT,
+                        entry_id: 1,
+                        maker_addr: SECOND_MAKER.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                vec![
+                    VoteRecord {
+                        votes: default_votes(775, 820, 1000, 250),
+                        height: first_block.height,
+                        time: first_block.time,
+                    },
+                    VoteRecord {
+                        votes: default_votes(800, 820, 900, 250),
+                        height: second_block.height,
+                        time: second_block.time,
+                    },
+                ]
+            );
+
+            let res: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.count, 1);
+            assert_eq!(res.total, Uint128::new(2770));
+        }
+
+        #[test]
+        fn test_final_ballots() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract_with_msg(
+                &mut app,
+                InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: makers_cw4_group.to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: None,
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: Some(0),
+                    equal_weights: None,
+                },
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(775, 820, 1000, 250),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr,
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: None,
+                        entry_id: 1,
+                        votes: default_votes(800, 820, 900, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::TooManyAmendments { max: 0 }.to_string()
+            );
+        }
+
+        #[test]
+        fn test_wrong_event() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
                 makers_cw4_group.to_string(),
             );
 
@@ -515,24 +1407,186 @@ mod execute {
                 "entry_genetics".to_string(),
                 "entry_farmer".to_string(),
             );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
 
-            let votes = Votes {
-                look: Uint128::new(775),
-                smell: Uint128::new(820),
-                taste: Uint128::new(1000),
-                post_melt: Uint128::new(250),
-            };
+            // Every event moves through its own phases
+            setup_event(&mut app, contract_addr.clone(), "Legends 2024".to_string());
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetPhase {
+                    event: 2,
+                    phase: Phase::Voting,
+                },
+                &[],
+            )
+            .unwrap();
 
             let err = app
                 .execute_contract(
                     Addr::unchecked(SECOND_MAKER),
                     contract_addr.clone(),
                     &ExecuteMsg::Vote {
-                        category: "category_2".to_string(),
+                        event: 2,
+                        category: None,
+                        entry_id: 1,
+                        votes: default_votes(775, 820, 1000, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidEvent {}.to_string()
+            );
+
+            // Entries of other events are not found
+            let err = app
+                .wrap()
+                .query_wasm_smart::<EntryTotals>(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: 2,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap_err();
+            assert!(err.to_string().contains("not found"));
+        }
+
+        #[test]
+        fn test_membership_snapshot() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            let voting_height = app.block_info().height;
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let res: EventResponse = app
+                .wrap()
+                .query_wasm_smart(contract_addr.clone(), &QueryMsg::Event { event: EVENT })
+                .unwrap();
+            assert_eq!(
+                res.makers_snapshot,
+                Some(MakersSnapshot {
+                    makers_cw4_group: makers_cw4_group.clone(),
+                    height: voting_height,
+                })
+            );
+
+            // Change the judges mid-event
+            app.update_block(next_block);
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                makers_cw4_group,
+                &Cw4ExecuteMsg::UpdateMembers {
+                    remove: vec![SECOND_MAKER.to_string()],
+                    add: vec![Member {
+                        addr: USER.to_string(),
+                        weight: 1,
+                    }],
+                },
+                &[],
+            )
+            .unwrap();
+            app.update_block(next_block);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes: default_votes(775, 820, 1000, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            // Removed judges keep their seat until the event is over
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(775, 820, 1000, 250),
+            );
+            let res: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.count, 1);
+        }
+
+        #[test]
+        fn test_invalid_category() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let votes = default_votes(775, 820, 1000, 250);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_2".to_string()),
                         entry_id: 1,
                         votes: votes.clone(),
                     },
-                    &vec![],
+                    &[],
                 )
                 .unwrap_err();
             assert_eq!(
@@ -540,160 +1594,4426 @@ mod execute {
                 ContractError::InvalidCategory {}.to_string()
             );
         }
-    }
-}
-
-mod query {
-    use crate::msg::TallyVotesResponse;
 
-    use super::*;
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
 
-    #[test]
-    fn test_categories() {
-        let mut app = mock_app();
-        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
-        let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
-        let contract_addr = setup_contract(
-            &mut app,
-            admin_cw4_group.to_string(),
-            makers_cw4_group.to_string(),
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+
+            let votes = default_votes(775, 820, 1000, 250);
+
+            // Voting has not been opened yet
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes: votes.clone(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Voting,
+                    current: Phase::Registration
+                }
+                .to_string()
+            );
+
+            // Voting has already been closed
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Voting,
+                    current: Phase::Closed
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn test_outside_voting_window() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+
+            // The event opens in 10 blocks and the category closes an hour later
+            let block = app.block_info();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetVotingWindow {
+                    event: EVENT,
+                    category: None,
+                    opens: Some(Scheduled::AtHeight(block.height + 10)),
+                    closes: None,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetVotingWindow {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    opens: None,
+                    closes: Some(Expiration::AtTime(block.time.plus_seconds(3600))),
+                },
+                &[],
+            )
+            .unwrap();
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let votes = default_votes(775, 820, 1000, 250);
+            let vote_msg = ExecuteMsg::Vote {
+                event: EVENT,
+                category: Some("category_1".to_string()),
+                entry_id: 1,
+                votes,
+            };
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &vote_msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::VotingNotOpen {}.to_string()
+            );
+
+            app.update_block(|block| {
+                block.height += 10;
+                block.time = block.time.plus_seconds(60);
+            });
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &vote_msg,
+                &[],
+            )
+            .unwrap();
+
+            app.update_block(|block| block.time = block.time.plus_seconds(3600));
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &vote_msg,
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::VotingClosed {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_invalid_score() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract_with_msg(
+                &mut app,
+                InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: makers_cw4_group.to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: Some(Uint128::new(25)),
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: None,
+                    equal_weights: None,
+                },
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let cases = [
+                ("look", Uint128::new(999999)),
+                ("smell", Uint128::new(50)),
+                ("taste", Uint128::new(1025)),
+                ("post_melt", Uint128::new(110)),
+            ];
+            for (criterion, value) in cases {
+                let mut votes = default_votes(775, 825, 1000, 250);
+                votes.insert(criterion.to_string(), value);
+
+                let err = app
+                    .execute_contract(
+                        Addr::unchecked(SECOND_MAKER),
+                        contract_addr.clone(),
+                        &ExecuteMsg::Vote {
+                            event: EVENT,
+                            category: Some("category_1".to_string()),
+                            entry_id: 1,
+                            votes,
+                        },
+                        &[],
+                    )
+                    .unwrap_err();
+                assert_eq!(
+                    err.source().unwrap().to_string(),
+                    ContractError::InvalidScore {
+                        criterion: criterion.to_string(),
+                        value
+                    }
+                    .to_string()
+                );
+            }
+        }
+
+        #[test]
+        fn test_custom_criteria() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_custom_category(
+                &mut app,
+                contract_addr.clone(),
+                "edibles".to_string(),
+                vec!["texture", "dosing_accuracy"],
+            );
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "edibles".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            // The default criteria do not apply to this category
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("edibles".to_string()),
+                        entry_id: 1,
+                        votes: default_votes(775, 820, 1000, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::UnknownCriterion {
+                    criterion: "look".to_string()
+                }
+                .to_string()
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("edibles".to_string()),
+                        entry_id: 1,
+                        votes: Votes::from([("texture".to_string(), Uint128::new(500))]),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::MissingCriterion {
+                    criterion: "dosing_accuracy".to_string()
+                }
+                .to_string()
+            );
+
+            let votes = Votes::from([
+                ("texture".to_string(), Uint128::new(500)),
+                ("dosing_accuracy".to_string(), Uint128::new(875)),
+            ]);
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::Vote {
+                    event: EVENT,
+                    category: Some("edibles".to_string()),
+                    entry_id: 1,
+                    votes: votes.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: TallyVotesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::TallyVotes {
+                        event: EVENT,
+                        entry_id: 1,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.votes[0].votes, votes);
+            assert_eq!(res.votes[0].sum, Uint128::new(1375));
+            assert_eq!(res.sum, votes);
+        }
+
+        #[test]
+        fn test_without_category() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let votes = default_votes(775, 820, 1000, 250);
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::Vote {
+                    event: EVENT,
+                    category: None,
+                    entry_id: 1,
+                    votes: votes.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: Votes = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Votes {
+                        event: EVENT,
+                        entry_id: 1,
+                        maker_addr: SECOND_MAKER.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res, votes);
+
+            // The self vote rule still applies without a category
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(FIRST_MAKER),
+                    contract_addr,
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: None,
+                        entry_id: 1,
+                        votes,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidMaker {}.to_string()
+            );
+        }
+    }
+
+    mod commit_reveal {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let contract_addr = setup_commit_reveal_contract(&mut app);
+
+            let votes = default_votes(775, 820, 1000, 250);
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::CommitVote {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    entry_id: 1,
+                    commitment: commitment(&votes, "pepper"),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // Committed votes are not part of the tally until revealed
+            let res: TallyVotesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::TallyVotes {
+                        event: EVENT,
+                        entry_id: 1,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert!(res.votes.is_empty());
+
+            let res: Vec<String> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::UnrevealedCommitments {
+                        event: EVENT,
+                        entry_id: 1,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res, vec![SECOND_MAKER.to_string()]);
+
+            setup_phase(&mut app, contract_addr.clone(), Phase::Reveal);
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::RevealVote {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    entry_id: 1,
+                    votes: votes.clone(),
+                    salt: "pepper".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: Votes = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Votes {
+                        event: EVENT,
+                        entry_id: 1,
+                        maker_addr: SECOND_MAKER.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res, votes);
+
+            let res: Vec<String> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::UnrevealedCommitments {
+                        event: EVENT,
+                        entry_id: 1,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert!(res.is_empty());
+        }
+
+        #[test]
+        fn test_commitment_mismatch() {
+            let mut app = mock_app();
+            let contract_addr = setup_commit_reveal_contract(&mut app);
+
+            let votes = default_votes(775, 820, 1000, 250);
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::CommitVote {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    entry_id: 1,
+                    commitment: commitment(&votes, "pepper"),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // Reveals are not accepted while voting is open
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::RevealVote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes: votes.clone(),
+                        salt: "pepper".to_string(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Reveal,
+                    current: Phase::Voting
+                }
+                .to_string()
+            );
+
+            setup_phase(&mut app, contract_addr.clone(), Phase::Reveal);
+
+            for (votes, salt) in [
+                (votes.clone(), "salt"),
+                (default_votes(775, 820, 1000, 1000), "pepper"),
+            ] {
+                let err = app
+                    .execute_contract(
+                        Addr::unchecked(SECOND_MAKER),
+                        contract_addr.clone(),
+                        &ExecuteMsg::RevealVote {
+                            event: EVENT,
+                            category: Some("category_1".to_string()),
+                            entry_id: 1,
+                            votes,
+                            salt: salt.to_string(),
+                        },
+                        &[],
+                    )
+                    .unwrap_err();
+                assert_eq!(
+                    err.source().unwrap().to_string(),
+                    ContractError::CommitmentMismatch {}.to_string()
+                );
+            }
+
+            // Makers that never committed have nothing to reveal
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("third_maker"),
+                    contract_addr.clone(),
+                    &ExecuteMsg::RevealVote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes,
+                        salt: "pepper".to_string(),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::NoCommitment {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_voting_mode() {
+            let mut app = mock_app();
+            let contract_addr = setup_commit_reveal_contract(&mut app);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes: default_votes(775, 820, 1000, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::CommitRevealEnabled {}.to_string()
+            );
+
+            // Without commit and reveal, voting goes straight to closed
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::CommitVote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        commitment: Binary::from(b"commitment"),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::CommitRevealDisabled {}.to_string()
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetPhase {
+                        event: EVENT,
+                        phase: Phase::Reveal,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidPhaseTransition {
+                    from: Phase::Voting,
+                    to: Phase::Reveal
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod vote_batch {
+        use super::*;
+
+        fn setup_batch_contract(app: &mut App) -> Addr {
+            let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+            let makers_cw4_group =
+                setup_cw4_group(app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+            let contract_addr = setup_contract(
+                app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(app, contract_addr.clone(), "category_1".to_string());
+            setup_custom_category(
+                app,
+                contract_addr.clone(),
+                "category_2".to_string(),
+                vec!["texture", "consistency"],
+            );
+            for (category, maker_addr) in [
+                ("category_1", FIRST_MAKER),
+                ("category_1", SECOND_MAKER),
+                ("category_2", FIRST_MAKER),
+            ] {
+                setup_entry(
+                    app,
+                    contract_addr.clone(),
+                    "entry_name".to_string(),
+                    category.to_string(),
+                    maker_addr.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(app, contract_addr.clone(), Phase::Voting);
+
+            contract_addr
+        }
+
+        fn entry_count(app: &App, contract_addr: Addr, entry_id: u64) -> u64 {
+            let res: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id,
+                    },
+                )
+                .unwrap();
+            res.count
+        }
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let contract_addr = setup_batch_contract(&mut app);
+
+            let texture_votes = Votes::from([
+                ("texture".to_string(), Uint128::new(900)),
+                ("consistency".to_string(), Uint128::new(700)),
+            ]);
+            let res = app
+                .execute_contract(
+                    Addr::unchecked("third_maker"),
+                    contract_addr.clone(),
+                    &ExecuteMsg::VoteBatch {
+                        event: EVENT,
+                        ballots: vec![
+                            Ballot {
+                                entry_id: 1,
+                                votes: default_votes(775, 820, 1000, 250),
+                            },
+                            Ballot {
+                                entry_id: 2,
+                                votes: default_votes(500, 500, 500, 500),
+                            },
+                            Ballot {
+                                entry_id: 3,
+                                votes: texture_votes.clone(),
+                            },
+                        ],
+                    },
+                    &[],
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "count" && attr.value == "3"));
+
+            let res: Votes = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Votes {
+                        event: EVENT,
+                        entry_id: 3,
+                        maker_addr: "third_maker".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res, texture_votes);
+            for entry_id in 1..=3 {
+                assert_eq!(entry_count(&app, contract_addr.clone(), entry_id), 1);
+            }
+        }
+
+        #[test]
+        fn test_invalid_ballot() {
+            let mut app = mock_app();
+            let contract_addr = setup_batch_contract(&mut app);
+
+            // The second maker cannot score their own entry
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::VoteBatch {
+                        event: EVENT,
+                        ballots: vec![
+                            Ballot {
+                                entry_id: 1,
+                                votes: default_votes(775, 820, 1000, 250),
+                            },
+                            Ballot {
+                                entry_id: 2,
+                                votes: default_votes(500, 500, 500, 500),
+                            },
+                        ],
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidBallot {
+                    index: 1,
+                    entry_id: 2,
+                    source: Box::new(ContractError::InvalidMaker {}),
+                }
+                .to_string()
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("third_maker"),
+                    contract_addr.clone(),
+                    &ExecuteMsg::VoteBatch {
+                        event: EVENT,
+                        ballots: vec![
+                            Ballot {
+                                entry_id: 1,
+                                votes: default_votes(775, 820, 1000, 250),
+                            },
+                            Ballot {
+                                entry_id: 3,
+                                votes: default_votes(500, 500, 500, 500),
+                            },
+                        ],
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidBallot {
+                    index: 1,
+                    entry_id: 3,
+                    source: Box::new(ContractError::UnknownCriterion {
+                        criterion: "look".to_string()
+                    }),
+                }
+                .to_string()
+            );
+
+            // Valid ballots of a rejected batch are not saved
+            assert_eq!(entry_count(&app, contract_addr, 1), 0);
+        }
+
+        #[test]
+        fn test_duplicate_entry() {
+            let mut app = mock_app();
+            let contract_addr = setup_batch_contract(&mut app);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("third_maker"),
+                    contract_addr.clone(),
+                    &ExecuteMsg::VoteBatch {
+                        event: EVENT,
+                        ballots: vec![
+                            Ballot {
+                                entry_id: 2,
+                                votes: default_votes(775, 820, 1000, 250),
+                            },
+                            Ballot {
+                                entry_id: 2,
+                                votes: default_votes(500, 500, 500, 500),
+                            },
+                        ],
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidBallot {
+                    index: 1,
+                    entry_id: 2,
+                    source: Box::new(ContractError::DuplicateEntry {}),
+                }
+                .to_string()
+            );
+            assert_eq!(entry_count(&app, contract_addr, 2), 0);
+        }
+
+        #[test]
+        fn test_empty_batch() {
+            let mut app = mock_app();
+            let contract_addr = setup_batch_contract(&mut app);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("third_maker"),
+                    contract_addr,
+                    &ExecuteMsg::VoteBatch {
+                        event: EVENT,
+                        ballots: vec![],
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::EmptyBatch {}.to_string()
+            );
+        }
+    }
+
+    mod retract_vote {
+        use super::*;
+
+        fn setup_voted_contract(app: &mut App) -> Addr {
+            let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+            let makers_cw4_group =
+                setup_cw4_group(app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+            let contract_addr = setup_contract(
+                app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(app, contract_addr.clone(), Phase::Voting);
+
+            setup_vote(
+                app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(775, 820, 1000, 250),
+            );
+            setup_vote(
+                app,
+                contract_addr.clone(),
+                "third_maker",
+                "category_1".to_string(),
+                1,
+                default_votes(500, 500, 500, 500),
+            );
+
+            contract_addr
+        }
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let contract_addr = setup_voted_contract(&mut app);
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::RetractVote {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                    &[],
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "action" && attr.value == "retract_vote"));
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "maker_addr" && attr.value == SECOND_MAKER));
+
+            // Only the remaining ballot is counted
+            let res: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.count, 1);
+            assert_eq!(res.sums, default_votes(500, 500, 500, 500));
+            assert_eq!(res.total, Uint128::new(2000));
+
+            let res: TallyVotesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::TallyVotes {
+                        event: EVENT,
+                        entry_id: 1,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.votes.len(), 1);
+            assert_eq!(res.votes[0].maker_addr, "third_maker");
+
+            // The retraction is kept in the history of the judge
+            let res: Vec<VoteRecord> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::VoteHistory {
+                        event: EVENT,
+                        entry_id: 1,
+                        maker_addr: SECOND_MAKER.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.len(), 2);
+            assert_eq!(res[0].votes, default_votes(775, 820, 1000, 250));
+            assert!(res[1].votes.is_empty());
+
+            // The judge can vote again while voting is open
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(600, 600, 600, 600),
+            );
+            let res: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.count, 2);
+            assert_eq!(res.total, Uint128::new(4400));
+        }
+
+        #[test]
+        fn test_no_vote() {
+            let mut app = mock_app();
+            let contract_addr = setup_voted_contract(&mut app);
+
+            app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::RetractVote {
+                    event: EVENT,
+                    entry_id: 1,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr,
+                    &ExecuteMsg::RetractVote {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::NoVote {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_voting_closed() {
+            let mut app = mock_app();
+            let contract_addr = setup_voted_contract(&mut app);
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetVotingWindow {
+                    event: EVENT,
+                    category: None,
+                    opens: None,
+                    closes: Some(Expiration::AtHeight(app.block_info().height + 1)),
+                },
+                &[],
+            )
+            .unwrap();
+            app.update_block(|block| block.height += 1);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::RetractVote {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::VotingClosed {}.to_string()
+            );
+
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr,
+                    &ExecuteMsg::RetractVote {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Voting,
+                    current: Phase::Closed,
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod set_phase {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            app.update_block(|block| block.height += 10);
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetPhase {
+                    event: EVENT,
+                    phase: Phase::Voting,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let block = app.block_info();
+            let res: PhaseInfo = app
+                .wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::Phase { event: EVENT })
+                .unwrap();
+            assert_eq!(
+                res,
+                PhaseInfo {
+                    phase: Phase::Voting,
+                    changed_at_height: block.height,
+                    changed_at_time: block.time,
+                }
+            );
+        }
+
+        #[test]
+        fn test_invalid_admin() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetPhase {
+                        event: EVENT,
+                        phase: Phase::Voting,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_invalid_transition() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            // Phases cannot be skipped
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetPhase {
+                        event: EVENT,
+                        phase: Phase::Closed,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidPhaseTransition {
+                    from: Phase::Registration,
+                    to: Phase::Closed
+                }
+                .to_string()
+            );
+
+            // Phases cannot move backwards
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetPhase {
+                        event: EVENT,
+                        phase: Phase::Registration,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidPhaseTransition {
+                    from: Phase::Voting,
+                    to: Phase::Registration
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod update_config {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let new_makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::UpdateConfig {
+                        admins_cw4_group: None,
+                        makers_cw4_group: Some(new_makers_cw4_group.to_string()),
+                    },
+                    &[],
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+            assert!(wasm
+                .attributes
+                .iter()
+                .any(|a| a.key == "old_makers_cw4_group" && a.value == makers_cw4_group.as_str()));
+            assert!(wasm.attributes.iter().any(
+                |a| a.key == "new_makers_cw4_group" && a.value == new_makers_cw4_group.as_str()
+            ));
+            assert!(!wasm
+                .attributes
+                .iter()
+                .any(|a| a.key == "new_admins_cw4_group"));
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+                .unwrap();
+            assert_eq!(res.admins_cw4_group, admin_cw4_group);
+            assert_eq!(res.makers_cw4_group, new_makers_cw4_group);
+
+            // The event keeps the judges of the makers group it opened voting with
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(SECOND_MAKER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Vote {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        entry_id: 1,
+                        votes: default_votes(775, 820, 1000, 250),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            // Events opening voting later take their judges from the new makers group
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::CreateEvent {
+                    name: "Legends 2024".to_string(),
+                    date: None,
+                    location: None,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetPhase {
+                    event: 2,
+                    phase: Phase::Voting,
+                },
+                &[],
+            )
+            .unwrap();
+            let res: EventResponse = app
+                .wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::Event { event: 2 })
+                .unwrap();
+            assert_eq!(
+                res.makers_snapshot
+                    .map(|snapshot| snapshot.makers_cw4_group),
+                Some(new_makers_cw4_group)
+            );
+        }
+
+        #[test]
+        fn test_mid_event_rotation() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            // The makers group is redeployed with the same members mid-event
+            let new_makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::UpdateConfig {
+                    admins_cw4_group: None,
+                    makers_cw4_group: Some(new_makers_cw4_group.to_string()),
+                },
+                &[],
+            )
+            .unwrap();
+
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                "category_1".to_string(),
+                1,
+                default_votes(775, 820, 1000, 250),
+            );
+
+            let res: EventResponse = app
+                .wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::Event { event: EVENT })
+                .unwrap();
+            assert_eq!(
+                res.makers_snapshot
+                    .map(|snapshot| snapshot.makers_cw4_group),
+                Some(makers_cw4_group)
+            );
+        }
+
+        #[test]
+        fn test_invalid_admin() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::UpdateConfig {
+                        admins_cw4_group: Some(USER.to_string()),
+                        makers_cw4_group: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_invalid_address() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::UpdateConfig {
+                    admins_cw4_group: Some("Juno..Admins".to_string()),
+                    makers_cw4_group: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+
+            let res: Config = app
+                .wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+                .unwrap();
+            assert_eq!(res.admins_cw4_group, admin_cw4_group);
+        }
+    }
+
+    mod set_voting_window {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetVotingWindow {
+                    event: EVENT,
+                    category: None,
+                    opens: Some(Scheduled::AtHeight(100)),
+                    closes: Some(Expiration::AtHeight(200)),
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::SetVotingWindow {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    opens: Some(Scheduled::AtHeight(150)),
+                    closes: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: VotingWindow = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::VotingWindow { event: EVENT },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                VotingWindow {
+                    opens: Some(Scheduled::AtHeight(100)),
+                    closes: Some(Expiration::AtHeight(200)),
+                }
+            );
+
+            let res: CategoryInfo = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::CategoryInfo {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                CategoryInfo {
+                    event: EVENT,
+                    name: "category_1".to_string(),
+                    criteria: vec![
+                        "look".to_string(),
+                        "smell".to_string(),
+                        "taste".to_string(),
+                        "post_melt".to_string()
+                    ],
+                    weights: BTreeMap::from([
+                        ("look".to_string(), Decimal::one()),
+                        ("smell".to_string(), Decimal::one()),
+                        ("taste".to_string(), Decimal::one()),
+                        ("post_melt".to_string(), Decimal::one()),
+                    ]),
+                    voting_window: VotingWindow {
+                        opens: Some(Scheduled::AtHeight(150)),
+                        closes: None,
+                    },
+                    min_ballots: None,
+                    scoring: ScoringMode::Mean,
+                    tie_breakers: vec![],
+                }
+            );
+        }
+
+        #[test]
+        fn test_invalid_admin() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetVotingWindow {
+                        event: EVENT,
+                        category: None,
+                        opens: Some(Scheduled::AtHeight(100)),
+                        closes: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_invalid_window() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetVotingWindow {
+                        event: EVENT,
+                        category: None,
+                        opens: Some(Scheduled::AtHeight(200)),
+                        closes: Some(Expiration::AtHeight(100)),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidVotingWindow {}.to_string()
+            );
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::SetVotingWindow {
+                        event: EVENT,
+                        category: Some("category_1".to_string()),
+                        opens: Some(Scheduled::AtHeight(100)),
+                        closes: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::InvalidCategory {}.to_string()
+            );
+        }
+    }
+
+    mod compute_medians {
+        use super::*;
+
+        fn setup_median_category(app: &mut App, contract_addr: Addr) {
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr,
+                &ExecuteMsg::AddCategory {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    criteria: Some(vec!["taste".to_string()]),
+                    weights: None,
+                    min_ballots: None,
+                    scoring: Some(ScoringMode::Median),
+                    tie_breakers: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(
+                &mut app,
+                vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+            );
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_median_category(&mut app, contract_addr.clone());
+            for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+                setup_entry(
+                    &mut app,
+                    contract_addr.clone(),
+                    name.to_string(),
+                    "category_1".to_string(),
+                    maker.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            // Entry 2 has the better mean, entry 1 the better median
+            for (voter, entry_id, taste) in [
+                (SECOND_MAKER, 1, 100),
+                ("third_maker", 1, 600),
+                ("fourth_maker", 1, 700),
+                (FIRST_MAKER, 2, 1000),
+                ("third_maker", 2, 500),
+                ("fourth_maker", 2, 520),
+            ] {
+                setup_vote(
+                    &mut app,
+                    contract_addr.clone(),
+                    voter,
+                    "category_1".to_string(),
+                    entry_id,
+                    Votes::from([("taste".to_string(), Uint128::new(taste))]),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+            // Medians count as zero until they are computed
+            let res: EntryScoresResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::EntryScores {
+                        event: EVENT,
+                        entry_id: 2,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.score, Decimal::zero());
+
+            for start_after in [None, Some(1)] {
+                let res = app
+                    .execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::ComputeMedians {
+                            event: EVENT,
+                            category: "category_1".to_string(),
+                            start_after,
+                            limit: Some(1),
+                        },
+                        &[],
+                    )
+                    .unwrap();
+                let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+                let last_entry_id = start_after.unwrap_or_default() + 1;
+                assert!(wasm
+                    .attributes
+                    .iter()
+                    .any(|a| a.key == "last_entry_id" && a.value == last_entry_id.to_string()));
+            }
+
+            let res: EntryScoresResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::EntryScores {
+                        event: EVENT,
+                        entry_id: 2,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.raw_score, Decimal::from_ratio(2020u128, 3u128));
+            assert_eq!(
+                res.scored,
+                BTreeMap::from([("taste".to_string(), Decimal::from_ratio(520u128, 1u128))])
+            );
+            assert_eq!(res.score, Decimal::from_ratio(520u128, 1u128));
+
+            let res: Vec<LeaderboardResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::Leaderboard {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res.iter()
+                    .map(|entry| (entry.entry_id, entry.score))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (1, Decimal::from_ratio(600u128, 1u128)),
+                    (2, Decimal::from_ratio(520u128, 1u128)),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_median_category(&mut app, contract_addr.clone());
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr,
+                    &ExecuteMsg::ComputeMedians {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Closed,
+                    current: Phase::Registration
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn test_not_median_scoring() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr,
+                    &ExecuteMsg::ComputeMedians {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        start_after: None,
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::NotMedianScoring {}.to_string()
+            );
+        }
+    }
+
+    mod normalize {
+        use super::*;
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(
+                &mut app,
+                vec![
+                    FIRST_MAKER,
+                    SECOND_MAKER,
+                    "third_maker",
+                    "fourth_maker",
+                    "fifth_maker",
+                ],
+            );
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_custom_category(
+                &mut app,
+                contract_addr.clone(),
+                "category_1".to_string(),
+                vec!["taste"],
+            );
+            for (name, maker) in [
+                ("entry_1", FIRST_MAKER),
+                ("entry_2", SECOND_MAKER),
+                ("entry_3", "fifth_maker"),
+            ] {
+                setup_entry(
+                    &mut app,
+                    contract_addr.clone(),
+                    name.to_string(),
+                    "category_1".to_string(),
+                    maker.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            // The third maker judges harshly and the fourth leniently, each giving their
+            // best score to a different entry
+            for (voter, entry_id, taste) in [
+                ("third_maker", 1, 500),
+                ("third_maker", 2, 300),
+                ("fourth_maker", 2, 800),
+                ("fourth_maker", 3, 1000),
+            ] {
+                setup_vote(
+                    &mut app,
+                    contract_addr.clone(),
+                    voter,
+                    "category_1".to_string(),
+                    entry_id,
+                    Votes::from([("taste".to_string(), Uint128::new(taste))]),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+            // Four ballots in pages of three take two calls per stage
+            for (stage, ballots, next_stage) in [
+                ("stats", "3", "stats"),
+                ("stats", "1", "scores"),
+                ("scores", "3", "scores"),
+                ("scores", "1", "done"),
+            ] {
+                let res = app
+                    .execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::Normalize {
+                            event: EVENT,
+                            limit: Some(3),
+                        },
+                        &[],
+                    )
+                    .unwrap();
+                let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+                for (key, value) in [
+                    ("stage", stage),
+                    ("ballots", ballots),
+                    ("next_stage", next_stage),
+                ] {
+                    assert!(wasm
+                        .attributes
+                        .iter()
+                        .any(|a| a.key == key && a.value == value));
+                }
+            }
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Normalize {
+                        event: EVENT,
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::AlreadyNormalized {}.to_string()
+            );
+
+            let res: ScoreStats = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::JudgeStats {
+                        event: EVENT,
+                        judge: "third_maker".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res,
+                ScoreStats {
+                    count: 2,
+                    sum: Uint128::new(800),
+                    sum_squares: Uint128::new(340000),
+                }
+            );
+
+            // Both best scores end up level, one spread above the overall mean of 6.50
+            let res: Vec<LeaderboardResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Leaderboard {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: Some(RankBy::Normalized),
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res.iter()
+                    .map(|entry| (entry.rank, entry.entry_id))
+                    .collect::<Vec<_>>(),
+                vec![(1, 1), (1, 3), (3, 2)]
+            );
+            let spread = res[0].score - Decimal::from_ratio(650u128, 1u128);
+            assert!(spread > Decimal::from_ratio(269u128, 1u128));
+            assert!(spread < Decimal::from_ratio(270u128, 1u128));
+            assert_eq!(
+                res[0].score + res[2].score,
+                Decimal::from_ratio(1300u128, 1u128)
+            );
+
+            let res: EntryScoresResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryScores {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.score, Decimal::from_ratio(500u128, 1u128));
+            assert_eq!(
+                res.normalized_score,
+                Some(Decimal::from_ratio(650u128, 1u128) + spread)
+            );
+        }
+
+        #[test]
+        fn test_zero_limit() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group =
+                setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_custom_category(
+                &mut app,
+                contract_addr.clone(),
+                "category_1".to_string(),
+                vec!["taste"],
+            );
+            for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+                setup_entry(
+                    &mut app,
+                    contract_addr.clone(),
+                    name.to_string(),
+                    "category_1".to_string(),
+                    maker.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+            for entry_id in [1, 2] {
+                setup_vote(
+                    &mut app,
+                    contract_addr.clone(),
+                    "third_maker",
+                    "category_1".to_string(),
+                    entry_id,
+                    Votes::from([("taste".to_string(), Uint128::new(500))]),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+            // Empty pages are not allowed to restart the stage
+            for (limit, ballots, next_stage) in [
+                (Some(1), "1", "stats"),
+                (Some(0), "1", "stats"),
+                (None, "0", "scores"),
+            ] {
+                let res = app
+                    .execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::Normalize {
+                            event: EVENT,
+                            limit,
+                        },
+                        &[],
+                    )
+                    .unwrap();
+                let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+                for (key, value) in [("ballots", ballots), ("next_stage", next_stage)] {
+                    assert!(wasm
+                        .attributes
+                        .iter()
+                        .any(|a| a.key == key && a.value == value));
+                }
+            }
+
+            let res: ScoreStats = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::JudgeStats {
+                        event: EVENT,
+                        judge: "third_maker".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.count, 2);
+        }
+
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Normalize {
+                        event: EVENT,
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Closed,
+                    current: Phase::Voting
+                }
+                .to_string()
+            );
+
+            let res: Option<Normalization> = app
+                .wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::Normalization { event: EVENT })
+                .unwrap();
+            assert_eq!(res, None);
+        }
+    }
+
+    mod finalize {
+        use super::*;
+
+        fn setup_closed_contract(app: &mut App, scoring: ScoringMode) -> Addr {
+            let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(
+                app,
+                vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+            );
+            let contract_addr = setup_contract(
+                app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::AddCategory {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    criteria: Some(vec!["taste".to_string()]),
+                    weights: None,
+                    min_ballots: None,
+                    scoring: Some(scoring),
+                    tie_breakers: None,
+                },
+                &[],
+            )
+            .unwrap();
+            for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+                setup_entry(
+                    app,
+                    contract_addr.clone(),
+                    name.to_string(),
+                    "category_1".to_string(),
+                    maker.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(app, contract_addr.clone(), Phase::Voting);
+
+            // Entry 2 has the better mean, entry 1 the better median
+            for (voter, entry_id, taste) in [
+                (SECOND_MAKER, 1, 100),
+                ("third_maker", 1, 600),
+                ("fourth_maker", 1, 700),
+                (FIRST_MAKER, 2, 1000),
+                ("third_maker", 2, 500),
+                ("fourth_maker", 2, 520),
+            ] {
+                setup_vote(
+                    app,
+                    contract_addr.clone(),
+                    voter,
+                    "category_1".to_string(),
+                    entry_id,
+                    Votes::from([("taste".to_string(), Uint128::new(taste))]),
+                );
+            }
+            setup_phase(app, contract_addr.clone(), Phase::Closed);
+
+            contract_addr
+        }
+
+        #[test]
+        fn test_happy_path() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let makers_cw4_group = setup_cw4_group(
+                &mut app,
+                vec![
+                    FIRST_MAKER,
+                    SECOND_MAKER,
+                    "third_maker",
+                    "fourth_maker",
+                    "fifth_maker",
+                ],
+            );
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                makers_cw4_group.to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            for (name, maker) in [
+                ("entry_1", FIRST_MAKER),
+                ("entry_2", SECOND_MAKER),
+                ("entry_3", "third_maker"),
+                ("entry_4", "fourth_maker"),
+            ] {
+                setup_entry(
+                    &mut app,
+                    contract_addr.clone(),
+                    name.to_string(),
+                    "category_1".to_string(),
+                    maker.to_string(),
+                    "maker_name".to_string(),
+                    "entry_breeder".to_string(),
+                    "entry_genetics".to_string(),
+                    "entry_farmer".to_string(),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            // Entry 4 gets no ballots and is not eligible
+            for (entry_id, score) in [(1, 500), (2, 900), (3, 700)] {
+                setup_vote(
+                    &mut app,
+                    contract_addr.clone(),
+                    "fifth_maker",
+                    "category_1".to_string(),
+                    entry_id,
+                    default_votes(score, score, score, score),
+                );
+            }
+            setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::Finalize {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let block = app.block_info();
+            let res: ResultsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Results {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res.first,
+                vec![Placement {
+                    rank: 1,
+                    entry_id: 2,
+                    name: "entry_2".to_string(),
+                    score: Decimal::from_ratio(3600u128, 1u128),
+                    count: 1,
+                    eligible: true,
+                    tied: false,
+                }]
+            );
+            assert_eq!(res.second[0].entry_id, 3);
+            assert_eq!(res.third[0].entry_id, 1);
+            assert_eq!(
+                res.placements
+                    .iter()
+                    .map(|placement| (placement.rank, placement.entry_id, placement.eligible))
+                    .collect::<Vec<_>>(),
+                vec![(1, 2, true), (2, 3, true), (3, 1, true), (4, 4, false)]
+            );
+            assert_eq!(res.rank_by, RankBy::Average);
+            assert_eq!(res.finalized_at_height, block.height);
+
+            // Results are stored once
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr,
+                    &ExecuteMsg::Finalize {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::CategoryFinalized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn test_medians_not_computed() {
+            let mut app = mock_app();
+            let contract_addr = setup_closed_contract(&mut app, ScoringMode::Median);
+
+            for (compute_up_to, missing) in [(None, 1), (Some(1), 2)] {
+                if let Some(limit) = compute_up_to {
+                    app.execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::ComputeMedians {
+                            event: EVENT,
+                            category: "category_1".to_string(),
+                            start_after: None,
+                            limit: Some(limit),
+                        },
+                        &[],
+                    )
+                    .unwrap();
+                };
+
+                let err = app
+                    .execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::Finalize {
+                            event: EVENT,
+                            category: "category_1".to_string(),
+                            rank_by: None,
+                        },
+                        &[],
+                    )
+                    .unwrap_err();
+                assert_eq!(
+                    err.source().unwrap().to_string(),
+                    ContractError::MediansNotComputed { entry_id: missing }.to_string()
+                );
+            }
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::ComputeMedians {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: Some(1),
+                    limit: None,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::Finalize {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res: ResultsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::Results {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                res.placements
+                    .iter()
+                    .map(|placement| (placement.rank, placement.entry_id, placement.score))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (1, 1, Decimal::from_ratio(600u128, 1u128)),
+                    (2, 2, Decimal::from_ratio(520u128, 1u128)),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_normalized() {
+            let mut app = mock_app();
+            let contract_addr = setup_closed_contract(&mut app, ScoringMode::Mean);
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Finalize {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: Some(RankBy::Normalized),
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::NotNormalized {}.to_string()
+            );
+
+            // Both stages fit in a single call each
+            for _ in 0..2 {
+                app.execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Normalize {
+                        event: EVENT,
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            }
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::Finalize {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: Some(RankBy::Normalized),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let leaderboard: Vec<LeaderboardResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Leaderboard {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: Some(RankBy::Normalized),
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            let res: ResultsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::Results {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.rank_by, RankBy::Normalized);
+            assert!(res.placements[0].score > Decimal::zero());
+            assert_eq!(
+                res.placements
+                    .iter()
+                    .map(|placement| (placement.entry_id, placement.score))
+                    .collect::<Vec<_>>(),
+                leaderboard
+                    .iter()
+                    .map(|entry| (entry.entry_id, entry.score))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn test_criterion_weights() {
+            for scoring in [ScoringMode::Mean, ScoringMode::TrimmedMean { trim: 1 }] {
+                let mut app = mock_app();
+                let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+                let makers_cw4_group =
+                    setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+                let contract_addr = setup_contract(
+                    &mut app,
+                    admin_cw4_group.to_string(),
+                    makers_cw4_group.to_string(),
+                );
+
+                app.execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::AddCategory {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        criteria: Some(vec!["look".to_string(), "taste".to_string()]),
+                        weights: Some(BTreeMap::from([(
+                            "taste".to_string(),
+                            Decimal::percent(200),
+                        )])),
+                        min_ballots: None,
+                        scoring: Some(scoring),
+                        tie_breakers: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+                for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+                    setup_entry(
+                        &mut app,
+                        contract_addr.clone(),
+                        name.to_string(),
+                        "category_1".to_string(),
+                        maker.to_string(),
+                        "maker_name".to_string(),
+                        "entry_breeder".to_string(),
+                        "entry_genetics".to_string(),
+                        "entry_farmer".to_string(),
+                    );
+                }
+                setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+                // Both entries are level without weights, taste counting double decides
+                for (entry_id, look, taste) in [(1, 1000, 100), (2, 100, 1000)] {
+                    setup_vote(
+                        &mut app,
+                        contract_addr.clone(),
+                        "third_maker",
+                        "category_1".to_string(),
+                        entry_id,
+                        Votes::from([
+                            ("look".to_string(), Uint128::new(look)),
+                            ("taste".to_string(), Uint128::new(taste)),
+                        ]),
+                    );
+                }
+                setup_phase(&mut app, contract_addr.clone(), Phase::Closed);
+
+                app.execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Finalize {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+
+                let res: ResultsResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract_addr,
+                        &QueryMsg::Results {
+                            event: EVENT,
+                            category: "category_1".to_string(),
+                        },
+                    )
+                    .unwrap();
+                assert_eq!(
+                    res.placements
+                        .iter()
+                        .map(|placement| (placement.rank, placement.entry_id, placement.score))
+                        .collect::<Vec<_>>(),
+                    vec![
+                        (1, 2, Decimal::from_ratio(2100u128, 1u128)),
+                        (2, 1, Decimal::from_ratio(1200u128, 1u128)),
+                    ]
+                );
+            }
+        }
+
+        #[test]
+        fn test_wrong_phase() {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            let contract_addr = setup_contract(
+                &mut app,
+                admin_cw4_group.to_string(),
+                "contract_address".to_string(),
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Finalize {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Closed,
+                    current: Phase::Registration
+                }
+                .to_string()
+            );
+
+            // Ballots can still change while voting is open
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Finalize {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.source().unwrap().to_string(),
+                ContractError::WrongPhase {
+                    expected: Phase::Closed,
+                    current: Phase::Voting
+                }
+                .to_string()
+            );
+
+            // Categories without results cannot be queried
+            app.wrap()
+                .query_wasm_smart::<ResultsResponse>(
+                    contract_addr,
+                    &QueryMsg::Results {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                    },
+                )
+                .unwrap_err();
+        }
+    }
+}
+
+mod migrate {
+    use super::*;
+
+    #[test]
+    fn test_entry_ids() {
+        let mut app = mock_app();
+        let contract_addr = setup_legacy_contract(&mut app, false);
+        migrate_contract(&mut app, contract_addr.clone());
+
+        let res: PhaseInfo = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Phase { event: EVENT })
+            .unwrap();
+        assert_eq!(res.phase, Phase::Registration);
+
+        // The single event of version 0.1.0 becomes the first event
+        let res: EventResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Event { event: EVENT })
+            .unwrap();
+        assert_eq!(res.data.name, "Legends 2022");
+
+        // The entry after the last u8 ID no longer overflows
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            "third_maker".to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+
+        let res: Vec<EntriesResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Entries {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![254, 255, 256]
+        );
+        assert_eq!(res[0].data.maker_addr, FIRST_MAKER);
+        assert_eq!(res[2].data.maker_addr, "third_maker");
+
+        // Migrated entries can be found by ID alone
+        let res: EntriesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Entry {
+                    event: EVENT,
+                    category: None,
+                    entry_id: 255,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.data.maker_addr, SECOND_MAKER);
+    }
+
+    #[test]
+    fn test_votes() {
+        let mut app = mock_app();
+        let contract_addr = setup_legacy_contract(&mut app, true);
+        migrate_contract(&mut app, contract_addr.clone());
+
+        // Voting had already started, so it stays open
+        let res: PhaseInfo = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Phase { event: EVENT })
+            .unwrap();
+        assert_eq!(res.phase, Phase::Voting);
+
+        let res: Votes = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Votes {
+                    event: EVENT,
+                    entry_id: 254,
+                    maker_addr: SECOND_MAKER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res, default_votes(775, 820, 1000, 250));
+
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            FIRST_MAKER,
+            "category_1".to_string(),
+            255,
+            default_votes(450, 259, 720, 180),
+        );
+
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 255,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.votes.len(), 1);
+        assert_eq!(res.votes[0].maker_addr, FIRST_MAKER);
+        assert_eq!(res.votes[0].sum, Uint128::new(1609));
+
+        // Totals are rebuilt from the migrated votes
+        let res: EntryTotals = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 254,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.count, 1);
+        assert_eq!(res.sums, default_votes(775, 820, 1000, 250));
+        assert_eq!(res.total, Uint128::new(2845));
+    }
+
+    #[test]
+    fn test_same_version() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+        let code_id = app.store_code(voting_contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: makers_cw4_group.to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: None,
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: None,
+                    equal_weights: None,
+                },
+                &[],
+                "Voting Contract",
+                Some(ADMIN.to_string()),
+            )
+            .unwrap();
+
+        setup_event(&mut app, contract_addr.clone(), "Legends 2023".to_string());
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            SECOND_MAKER,
+            "category_1".to_string(),
+            1,
+            default_votes(775, 820, 1000, 250),
+        );
+
+        let res = app
+            .migrate_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &MigrateMsg {},
+                code_id,
+            )
+            .unwrap();
+        let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(wasm
+            .attributes
+            .iter()
+            .any(|a| a.key == "from_version" && a.value == env!("CARGO_PKG_VERSION")));
+
+        // No migration step runs, so the state is left untouched
+        let res: PhaseInfo = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Phase { event: EVENT })
+            .unwrap();
+        assert_eq!(res.phase, Phase::Voting);
+
+        let res: Votes = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Votes {
+                    event: EVENT,
+                    entry_id: 1,
+                    maker_addr: SECOND_MAKER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(res, default_votes(775, 820, 1000, 250));
+    }
+
+    #[test]
+    fn test_cannot_downgrade() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let code_id = app.store_code(newer_voting_contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: "contract_address".to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: None,
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: None,
+                    equal_weights: None,
+                },
+                &[],
+                "Voting Contract",
+                Some(ADMIN.to_string()),
+            )
+            .unwrap();
+
+        let code_id = app.store_code(voting_contract());
+        let err = app
+            .migrate_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr,
+                &MigrateMsg {},
+                code_id,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            ContractError::CannotDowngrade {
+                from: "99.0.0".to_string(),
+                to: env!("CARGO_PKG_VERSION").to_string(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_contract_name() {
+        let mut app = mock_app();
+        let code_id = app.store_code(cw4_group_contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &Cw4InstantiateMsg {
+                    admin: Some(ADMIN.to_string()),
+                    members: vec![],
+                },
+                &[],
+                "CW4 Group",
+                Some(ADMIN.to_string()),
+            )
+            .unwrap();
+
+        let code_id = app.store_code(voting_contract());
+        let err = app
+            .migrate_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr,
+                &MigrateMsg {},
+                code_id,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            ContractError::InvalidContractName {
+                name: "crates.io:cw4-group".to_string()
+            }
+            .to_string()
+        );
+    }
+}
+
+mod query {
+    use super::*;
+
+    #[test]
+    fn test_legacy_votes() {
+        // Ballots stored before criteria became configurable
+        let legacy = br#"{"look":"920","smell":"280","taste":"670","post_melt":"125"}"#;
+        let votes: Votes = from_slice(legacy).unwrap();
+        assert_eq!(votes, default_votes(920, 280, 670, 125));
+    }
+
+    #[test]
+    fn test_categories() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_category(&mut app, contract_addr.clone(), "category_2".to_string());
+        setup_category(&mut app, contract_addr.clone(), "category_3".to_string());
+
+        let res: Vec<String> = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Categories { event: EVENT })
+            .unwrap();
+        assert_eq!(res, vec!["category_1", "category_2", "category_3"]);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            "contract_address".to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_category(&mut app, contract_addr.clone(), "category_2".to_string());
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_2".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+
+        for category in [None, Some("category_2".to_string())] {
+            let res: EntriesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Entry {
+                        event: EVENT,
+                        category,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.id, 1);
+            assert_eq!(res.data.category, "category_2");
+            assert_eq!(res.data.maker_addr, FIRST_MAKER);
+        }
+
+        app.wrap()
+            .query_wasm_smart::<EntriesResponse>(
+                contract_addr,
+                &QueryMsg::Entry {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    entry_id: 1,
+                },
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            SECOND_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            "third_maker".to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+
+        let res: Vec<EntriesResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Entries {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(
+            res[1].data,
+            Entry {
+                name: "entry_name".to_string(),
+                category: "category_1".to_string(),
+                maker_addr: Addr::unchecked(SECOND_MAKER.to_string()),
+                maker_name: "maker_name".to_string(),
+                breeder: "entry_breeder".to_string(),
+                genetics: "entry_genetics".to_string(),
+                farmer: "entry_farmer".to_string(),
+            }
+        );
+
+        let res: Vec<EntriesResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Entries {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: Some(2),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[0].data,
+            Entry {
+                name: "entry_name".to_string(),
+                category: "category_1".to_string(),
+                maker_addr: Addr::unchecked("third_maker".to_string()),
+                maker_name: "maker_name".to_string(),
+                breeder: "entry_breeder".to_string(),
+                genetics: "entry_genetics".to_string(),
+                farmer: "entry_farmer".to_string(),
+            }
+        );
+
+        let res: Vec<EntriesResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Entries {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: Some(1),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[0].data,
+            Entry {
+                name: "entry_name".to_string(),
+                category: "category_1".to_string(),
+                maker_addr: Addr::unchecked(SECOND_MAKER.to_string()),
+                maker_name: "maker_name".to_string(),
+                breeder: "entry_breeder".to_string(),
+                genetics: "entry_genetics".to_string(),
+                farmer: "entry_farmer".to_string(),
+            }
+        )
+    }
+
+    #[test]
+    fn test_votes() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![
+                FIRST_MAKER,
+                SECOND_MAKER,
+                "third_maker",
+                "fourth_maker",
+                "fifth_maker",
+            ],
+        );
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            SECOND_MAKER,
+            "category_1".to_string(),
+            1,
+            default_votes(775, 820, 1000, 250),
+        );
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "third_maker",
+            "category_1".to_string(),
+            1,
+            default_votes(450, 259, 720, 180),
+        );
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "fourth_maker",
+            "category_1".to_string(),
+            1,
+            default_votes(603, 278, 383, 286),
+        );
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "fifth_maker",
+            "category_1".to_string(),
+            1,
+            default_votes(950, 279, 632, 492),
+        );
+
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.votes.len(), 4);
+        assert_eq!(res.votes[0].maker_addr, "fifth_maker");
+        assert_eq!(res.votes[0].sum, Uint128::new(2353));
+        assert_eq!(res.votes[1].maker_addr, "fourth_maker");
+        assert_eq!(res.votes[1].sum, Uint128::new(1550));
+        assert_eq!(res.sum["look"], Uint128::new(2778));
+        assert_eq!(res.sum["smell"], Uint128::new(1636));
+        assert_eq!(res.sum["taste"], Uint128::new(2735));
+        assert_eq!(res.sum["post_melt"], Uint128::new(1208));
+
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 1,
+                    start_after: Some("fourth_maker".to_string()),
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.votes.len(), 2);
+        assert_eq!(res.votes[0].maker_addr, SECOND_MAKER);
+        assert_eq!(res.votes[0].sum, Uint128::new(2845));
+        assert_eq!(res.votes[1].maker_addr, "third_maker");
+        assert_eq!(res.votes[1].sum, Uint128::new(1609));
+        assert_eq!(res.sum["look"], Uint128::new(1225));
+        assert_eq!(res.sum["smell"], Uint128::new(1079));
+        assert_eq!(res.sum["taste"], Uint128::new(1720));
+        assert_eq!(res.sum["post_melt"], Uint128::new(430));
+    }
+
+    #[test]
+    fn test_weighted_votes() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group =
+            setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER, "third_maker"]);
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_1".to_string(),
+                criteria: None,
+                weights: Some(BTreeMap::from([
+                    ("taste".to_string(), Decimal::percent(200)),
+                    ("post_melt".to_string(), Decimal::percent(150)),
+                ])),
+                min_ballots: None,
+                scoring: None,
+                tie_breakers: None,
+            },
+            &[],
+        )
+        .unwrap();
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            SECOND_MAKER,
+            "category_1".to_string(),
+            1,
+            default_votes(775, 820, 1000, 250),
+        );
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "third_maker",
+            "category_1".to_string(),
+            1,
+            default_votes(450, 259, 720, 181),
+        );
+
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        // 775 + 820 + 1000 * 2 + 250 * 1.5
+        assert_eq!(res.votes[0].maker_addr, SECOND_MAKER);
+        assert_eq!(res.votes[0].sum, Uint128::new(2845));
+        assert_eq!(
+            res.votes[0].weighted_sum,
+            Decimal::from_ratio(3970u128, 1u128)
+        );
+        // 450 + 259 + 720 * 2 + 181 * 1.5
+        assert_eq!(
+            res.votes[1].weighted_sum,
+            Decimal::from_ratio(4841u128, 2u128)
+        );
+        assert_eq!(res.weighted_sum, Decimal::from_ratio(12781u128, 2u128));
+    }
+
+    fn setup_judge_weights_contract(app: &mut App, equal_weights: Option<bool>) -> Addr {
+        let admin_cw4_group = setup_cw4_group(app, vec![ADMIN]);
+        // The second maker is a head judge
+        let makers_cw4_group = setup_weighted_cw4_group(
+            app,
+            vec![
+                (FIRST_MAKER, 1),
+                (SECOND_MAKER, 2),
+                ("third_maker", 1),
+                ("fourth_maker", 1),
+            ],
+        );
+        let contract_addr = setup_contract_with_msg(
+            app,
+            InstantiateMsg {
+                admins_cw4_group: admin_cw4_group.to_string(),
+                makers_cw4_group: makers_cw4_group.to_string(),
+                min_score: None,
+                max_score: None,
+                score_step: None,
+                commit_reveal: None,
+                min_ballots: None,
+                max_amendments: None,
+                equal_weights,
+            },
+        );
+
+        setup_category(app, contract_addr.clone(), "category_1".to_string());
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_2".to_string(),
+                criteria: None,
+                weights: None,
+                min_ballots: None,
+                scoring: Some(ScoringMode::TrimmedMean { trim: 1 }),
+                tie_breakers: None,
+            },
+            &[],
+        )
+        .unwrap();
+        for category in ["category_1", "category_2"] {
+            setup_entry(
+                app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                category.to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(app, contract_addr.clone(), Phase::Voting);
+
+        for (category, entry_id) in [("category_1", 1), ("category_2", 2)] {
+            setup_vote(
+                app,
+                contract_addr.clone(),
+                SECOND_MAKER,
+                category.to_string(),
+                entry_id,
+                default_votes(900, 900, 900, 900),
+            );
+            setup_vote(
+                app,
+                contract_addr.clone(),
+                "third_maker",
+                category.to_string(),
+                entry_id,
+                default_votes(300, 300, 300, 300),
+            );
+        }
+        setup_vote(
+            app,
+            contract_addr.clone(),
+            "fourth_maker",
+            "category_2".to_string(),
+            2,
+            default_votes(600, 600, 600, 600),
+        );
+
+        contract_addr
+    }
+
+    #[test]
+    fn test_judge_weights() {
+        let mut app = mock_app();
+        let contract_addr = setup_judge_weights_contract(&mut app, None);
+
+        let res: EntryTotals = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            EntryTotals {
+                count: 2,
+                weight: 3,
+                sums: default_votes(2100, 2100, 2100, 2100),
+                total: Uint128::new(8400),
+                weighted_total: Decimal::from_ratio(8400u128, 1u128),
+            }
+        );
+
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.votes[0].maker_addr, SECOND_MAKER);
+        assert_eq!(res.votes[0].weight, 2);
+        assert_eq!(res.votes[0].sum, Uint128::new(3600));
+        assert_eq!(res.votes[1].weight, 1);
+        assert_eq!(res.sum, default_votes(2100, 2100, 2100, 2100));
+
+        // (3600 * 2 + 1200) / 3
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res[0].score, Decimal::from_ratio(2800u128, 1u128));
+
+        // Trimming drops the whole ballot of the head judge, leaving 600 per criterion
+        let res: EntryScoresResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::EntryScores {
+                    event: EVENT,
+                    entry_id: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.raw_score, Decimal::from_ratio(2700u128, 1u128));
+        assert_eq!(res.score, Decimal::from_ratio(2400u128, 1u128));
+    }
+
+    #[test]
+    fn test_equal_weights() {
+        let mut app = mock_app();
+        let contract_addr = setup_judge_weights_contract(&mut app, Some(true));
+
+        let res: EntryTotals = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.count, 2);
+        assert_eq!(res.weight, 2);
+        assert_eq!(res.total, Uint128::new(4800));
+
+        // (3600 + 1200) / 2
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res[0].score, Decimal::from_ratio(2400u128, 1u128));
+
+        // Trimming drops the lowest and highest ballot, leaving 600 per criterion
+        let res: EntryScoresResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::EntryScores {
+                    event: EVENT,
+                    entry_id: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.score, Decimal::from_ratio(2400u128, 1u128));
+    }
+
+    #[test]
+    fn test_zero_weight() {
+        for equal_weights in [None, Some(true)] {
+            let mut app = mock_app();
+            let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+            // The second maker is a member without any weight
+            let makers_cw4_group =
+                setup_weighted_cw4_group(&mut app, vec![(FIRST_MAKER, 1), (SECOND_MAKER, 0)]);
+            let contract_addr = setup_contract_with_msg(
+                &mut app,
+                InstantiateMsg {
+                    admins_cw4_group: admin_cw4_group.to_string(),
+                    makers_cw4_group: makers_cw4_group.to_string(),
+                    min_score: None,
+                    max_score: None,
+                    score_step: None,
+                    commit_reveal: None,
+                    min_ballots: None,
+                    max_amendments: None,
+                    equal_weights,
+                },
+            );
+
+            setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                "entry_name".to_string(),
+                "category_1".to_string(),
+                FIRST_MAKER.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+            setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+            let res = app.execute_contract(
+                Addr::unchecked(SECOND_MAKER),
+                contract_addr.clone(),
+                &ExecuteMsg::Vote {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    entry_id: 1,
+                    votes: default_votes(775, 820, 1000, 250),
+                },
+                &[],
+            );
+
+            // Weightless judges only vote when every ballot counts once
+            let totals: EntryTotals = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::EntryTotals {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            if equal_weights.is_some() {
+                res.unwrap();
+                assert_eq!((totals.count, totals.weight), (1, 1));
+            } else {
+                assert_eq!(
+                    res.unwrap_err().root_cause().to_string(),
+                    ContractError::Unauthorized {}.to_string()
+                );
+                assert_eq!((totals.count, totals.weight), (0, 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_entry_totals() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+        );
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_entry(
+            &mut app,
+            contract_addr.clone(),
+            "entry_name".to_string(),
+            "category_1".to_string(),
+            FIRST_MAKER.to_string(),
+            "maker_name".to_string(),
+            "entry_breeder".to_string(),
+            "entry_genetics".to_string(),
+            "entry_farmer".to_string(),
+        );
+
+        // Entries without votes have empty totals
+        let res: EntryTotals = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(res, EntryTotals::default());
+
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+        for (voter, votes) in [
+            (SECOND_MAKER, default_votes(775, 820, 1000, 250)),
+            ("third_maker", default_votes(450, 259, 720, 180)),
+            ("fourth_maker", default_votes(603, 278, 383, 286)),
+            // Overwrites the earlier votes of the second maker
+            (SECOND_MAKER, default_votes(950, 279, 632, 492)),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                voter,
+                "category_1".to_string(),
+                1,
+                votes,
+            );
+        }
+
+        let res: EntryTotals = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res,
+            EntryTotals {
+                count: 3,
+                weight: 3,
+                sums: default_votes(2003, 816, 1735, 958),
+                total: Uint128::new(5512),
+                weighted_total: Decimal::from_ratio(5512u128, 1u128),
+            }
+        );
+
+        // Totals cover every vote, not only a single page of the tally
+        let res: TallyVotesResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::TallyVotes {
+                    event: EVENT,
+                    entry_id: 1,
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.sum, default_votes(603, 278, 383, 286));
+
+        app.wrap()
+            .query_wasm_smart::<EntryTotals>(
+                contract_addr,
+                &QueryMsg::EntryTotals {
+                    event: EVENT,
+                    entry_id: 2,
+                },
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+        );
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_1".to_string(),
+                criteria: None,
+                weights: Some(BTreeMap::from([(
+                    "taste".to_string(),
+                    Decimal::percent(200),
+                )])),
+                min_ballots: None,
+                scoring: None,
+                tie_breakers: None,
+            },
+            &[],
+        )
+        .unwrap();
+        for (name, maker) in [
+            ("entry_1", FIRST_MAKER),
+            ("entry_2", SECOND_MAKER),
+            ("entry_3", "third_maker"),
+        ] {
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                name.to_string(),
+                "category_1".to_string(),
+                maker.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+        // Entry 1 gets two average votes, entry 2 one great vote and entry 3 none
+        for (voter, entry_id, votes) in [
+            (SECOND_MAKER, 1, default_votes(500, 500, 500, 500)),
+            ("fourth_maker", 1, default_votes(500, 500, 400, 500)),
+            ("fourth_maker", 2, default_votes(900, 900, 1000, 1000)),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                voter,
+                "category_1".to_string(),
+                entry_id,
+                votes,
+            );
+        }
+
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: Some(RankBy::Total),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res[0],
+            LeaderboardResponse {
+                rank: 1,
+                entry_id: 1,
+                name: "entry_1".to_string(),
+                category: "category_1".to_string(),
+                score: Decimal::from_ratio(3900u128, 1u128),
+                count: 2,
+                sums: default_votes(1000, 1000, 900, 1000),
+                eligible: true,
+                tied: false,
+            }
+        );
+        assert_eq!(res[1].entry_id, 2);
+        assert_eq!(res[1].score, Decimal::from_ratio(3800u128, 1u128));
+        assert_eq!(res[2].entry_id, 3);
+        assert_eq!(res[2].count, 0);
+        assert!(!res[2].eligible);
+
+        // Entries are ranked by their mean score by default, with taste counting double
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            (res[0].entry_id, res[0].score),
+            (2, Decimal::from_ratio(4800u128, 1u128))
+        );
+        assert_eq!(
+            (res[1].entry_id, res[1].score),
+            (1, Decimal::from_ratio(2400u128, 1u128))
+        );
+
+        // Taste counts double, which puts both entries level until entry 1 wins on ballots
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: Some(RankBy::WeightedTotal),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.iter()
+                .map(|entry| (entry.rank, entry.entry_id, entry.score))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 1, Decimal::from_ratio(4800u128, 1u128)),
+                (2, 2, Decimal::from_ratio(4800u128, 1u128)),
+                (3, 3, Decimal::zero()),
+            ]
+        );
+        assert!(res.iter().all(|entry| !entry.tied));
+    }
+
+    #[test]
+    fn test_criterion_leaderboard() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+        );
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
+        setup_custom_category(
+            &mut app,
+            contract_addr.clone(),
+            "category_2".to_string(),
+            vec!["taste", "aroma"],
+        );
+        setup_custom_category(
+            &mut app,
+            contract_addr.clone(),
+            "category_3".to_string(),
+            vec!["look"],
+        );
+        for (name, category, maker) in [
+            ("entry_1", "category_1", FIRST_MAKER),
+            ("entry_2", "category_2", SECOND_MAKER),
+            ("entry_3", "category_3", "third_maker"),
+        ] {
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                name.to_string(),
+                category.to_string(),
+                maker.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+        let votes = |taste: u128, aroma: u128| {
+            Votes::from([
+                ("taste".to_string(), Uint128::new(taste)),
+                ("aroma".to_string(), Uint128::new(aroma)),
+            ])
+        };
+        for (voter, category, entry_id, votes) in [
+            (
+                SECOND_MAKER,
+                "category_1",
+                1,
+                default_votes(900, 900, 600, 900),
+            ),
+            (
+                "fourth_maker",
+                "category_1",
+                1,
+                default_votes(100, 100, 800, 100),
+            ),
+            ("third_maker", "category_2", 2, votes(700, 200)),
+            ("fourth_maker", "category_2", 2, votes(900, 1000)),
+            (
+                "fourth_maker",
+                "category_3",
+                3,
+                Votes::from([("look".to_string(), Uint128::new(1000))]),
+            ),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                voter,
+                category.to_string(),
+                entry_id,
+                votes,
+            );
+        }
+
+        // Ranks every category judging taste, the third category is left out
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::CriterionLeaderboard {
+                    event: EVENT,
+                    category: None,
+                    criterion: "taste".to_string(),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.iter()
+                .map(|entry| (
+                    entry.rank,
+                    entry.entry_id,
+                    entry.category.as_str(),
+                    entry.score
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 2, "category_2", Decimal::from_ratio(800u128, 1u128)),
+                (2, 1, "category_1", Decimal::from_ratio(700u128, 1u128)),
+            ]
+        );
+
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::CriterionLeaderboard {
+                    event: EVENT,
+                    category: Some("category_1".to_string()),
+                    criterion: "look".to_string(),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            (res[0].entry_id, res[0].score),
+            (1, Decimal::from_ratio(500u128, 1u128))
+        );
+
+        // The criterion must be judged in the requested category
+        app.wrap()
+            .query_wasm_smart::<Vec<LeaderboardResponse>>(
+                contract_addr,
+                &QueryMsg::CriterionLeaderboard {
+                    event: EVENT,
+                    category: Some("category_3".to_string()),
+                    criterion: "taste".to_string(),
+                    limit: None,
+                },
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_quorum() {
+        let mut app = mock_app();
+        let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![FIRST_MAKER, SECOND_MAKER, "third_maker", "fourth_maker"],
+        );
+        let contract_addr = setup_contract(
+            &mut app,
+            admin_cw4_group.to_string(),
+            makers_cw4_group.to_string(),
+        );
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_1".to_string(),
+                criteria: None,
+                weights: None,
+                min_ballots: Some(2),
+                scoring: None,
+                tie_breakers: None,
+            },
+            &[],
+        )
+        .unwrap();
+        for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                name.to_string(),
+                "category_1".to_string(),
+                maker.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+        // Entry 2 has the better mean but only a single ballot
+        for (voter, entry_id, votes) in [
+            (SECOND_MAKER, 1, default_votes(500, 500, 500, 500)),
+            ("third_maker", 1, default_votes(600, 600, 600, 600)),
+            ("fourth_maker", 2, default_votes(900, 900, 900, 900)),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                voter,
+                "category_1".to_string(),
+                entry_id,
+                votes,
+            );
+        }
+
+        let res: Vec<EntriesResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Entries {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.iter()
+                .map(|entry| (entry.id, entry.ballots, entry.eligible))
+                .collect::<Vec<_>>(),
+            vec![(1, 2, true), (2, 1, false)]
+        );
+
+        let res: Vec<LeaderboardResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
+                    category: "category_1".to_string(),
+                    rank_by: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.iter()
+                .map(|entry| (entry.rank, entry.entry_id, entry.score, entry.eligible))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 1, Decimal::from_ratio(2200u128, 1u128), true),
+                (2, 2, Decimal::from_ratio(3600u128, 1u128), false),
+            ]
         );
 
-        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
-        setup_category(&mut app, contract_addr.clone(), "category_2".to_string());
-        setup_category(&mut app, contract_addr.clone(), "category_3".to_string());
-
-        let res: Vec<String> = app
+        // A second ballot brings entry 2 to the quorum
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "third_maker",
+            "category_1".to_string(),
+            2,
+            default_votes(900, 900, 900, 900),
+        );
+        let res: EntriesResponse = app
             .wrap()
-            .query_wasm_smart(contract_addr, &QueryMsg::Categories {})
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Entry {
+                    event: EVENT,
+                    category: None,
+                    entry_id: 2,
+                },
+            )
             .unwrap();
-        assert_eq!(res, vec!["category_1", "category_2", "category_3"]);
+        assert_eq!((res.ballots, res.eligible), (2, true));
     }
 
     #[test]
-    fn test_entries() {
+    fn test_trimmed_mean() {
         let mut app = mock_app();
         let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
-        let makers_cw4_group = setup_cw4_group(&mut app, vec![FIRST_MAKER, SECOND_MAKER]);
+        let makers_cw4_group = setup_cw4_group(
+            &mut app,
+            vec![
+                FIRST_MAKER,
+                SECOND_MAKER,
+                "third_maker",
+                "fourth_maker",
+                "fifth_maker",
+            ],
+        );
         let contract_addr = setup_contract(
             &mut app,
             admin_cw4_group.to_string(),
             makers_cw4_group.to_string(),
         );
 
-        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
-        setup_entry(
-            &mut app,
-            contract_addr.clone(),
-            "entry_name".to_string(),
-            "category_1".to_string(),
-            FIRST_MAKER.to_string(),
-            "maker_name".to_string(),
-            "entry_breeder".to_string(),
-            "entry_genetics".to_string(),
-            "entry_farmer".to_string(),
-        );
-        setup_entry(
-            &mut app,
-            contract_addr.clone(),
-            "entry_name".to_string(),
-            "category_1".to_string(),
-            SECOND_MAKER.to_string(),
-            "maker_name".to_string(),
-            "entry_breeder".to_string(),
-            "entry_genetics".to_string(),
-            "entry_farmer".to_string(),
-        );
-        setup_entry(
-            &mut app,
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
             contract_addr.clone(),
-            "entry_name".to_string(),
-            "category_1".to_string(),
-            "third_maker".to_string(),
-            "maker_name".to_string(),
-            "entry_breeder".to_string(),
-            "entry_genetics".to_string(),
-            "entry_farmer".to_string(),
-        );
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_1".to_string(),
+                criteria: Some(vec!["taste".to_string(), "look".to_string()]),
+                weights: None,
+                min_ballots: None,
+                scoring: Some(ScoringMode::TrimmedMean { trim: 1 }),
+                tie_breakers: None,
+            },
+            &[],
+        )
+        .unwrap();
+        for (name, maker) in [("entry_1", FIRST_MAKER), ("entry_2", SECOND_MAKER)] {
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                name.to_string(),
+                "category_1".to_string(),
+                maker.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
 
-        let res: Vec<EntriesResponse> = app
+        let votes = |taste: u128, look: u128| {
+            Votes::from([
+                ("taste".to_string(), Uint128::new(taste)),
+                ("look".to_string(), Uint128::new(look)),
+            ])
+        };
+        // Entry 2 has too few ballots to trim and falls back to the mean
+        for (voter, entry_id, votes) in [
+            (SECOND_MAKER, 1, votes(100, 500)),
+            ("third_maker", 1, votes(600, 600)),
+            ("fourth_maker", 1, votes(700, 700)),
+            ("fifth_maker", 1, votes(1000, 900)),
+            ("third_maker", 2, votes(800, 800)),
+            ("fourth_maker", 2, votes(600, 600)),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                voter,
+                "category_1".to_string(),
+                entry_id,
+                votes,
+            );
+        }
+
+        let res: EntryScoresResponse = app
             .wrap()
             .query_wasm_smart(
                 contract_addr.clone(),
-                &QueryMsg::Entries {
-                    category: "category_1".to_string(),
-                    start_after: None,
-                    limit: None,
+                &QueryMsg::EntryScores {
+                    event: EVENT,
+                    entry_id: 1,
                 },
             )
             .unwrap();
-        assert_eq!(res.len(), 3);
         assert_eq!(
-            res[1].data,
-            Entry {
-                name: "entry_name".to_string(),
-                category: "category_1".to_string(),
-                maker_addr: Addr::unchecked(SECOND_MAKER.to_string()),
-                maker_name: "maker_name".to_string(),
-                breeder: "entry_breeder".to_string(),
-                genetics: "entry_genetics".to_string(),
-                farmer: "entry_farmer".to_string(),
+            res,
+            EntryScoresResponse {
+                entry_id: 1,
+                scoring: ScoringMode::TrimmedMean { trim: 1 },
+                count: 4,
+                raw: BTreeMap::from([
+                    ("look".to_string(), Decimal::from_ratio(675u128, 1u128)),
+                    ("taste".to_string(), Decimal::from_ratio(600u128, 1u128)),
+                ]),
+                raw_score: Decimal::from_ratio(1275u128, 1u128),
+                scored: BTreeMap::from([
+                    ("look".to_string(), Decimal::from_ratio(650u128, 1u128)),
+                    ("taste".to_string(), Decimal::from_ratio(650u128, 1u128)),
+                ]),
+                score: Decimal::from_ratio(1300u128, 1u128),
+                normalized_score: None,
             }
         );
 
-        let res: Vec<EntriesResponse> = app
+        let res: Vec<LeaderboardResponse> = app
             .wrap()
             .query_wasm_smart(
                 contract_addr.clone(),
-                &QueryMsg::Entries {
+                &QueryMsg::Leaderboard {
+                    event: EVENT,
                     category: "category_1".to_string(),
-                    start_after: Some(2),
+                    rank_by: None,
                     limit: None,
                 },
             )
             .unwrap();
-        assert_eq!(res.len(), 1);
         assert_eq!(
-            res[0].data,
-            Entry {
-                name: "entry_name".to_string(),
-                category: "category_1".to_string(),
-                maker_addr: Addr::unchecked("third_maker".to_string()),
-                maker_name: "maker_name".to_string(),
-                breeder: "entry_breeder".to_string(),
-                genetics: "entry_genetics".to_string(),
-                farmer: "entry_farmer".to_string(),
-            }
+            res.iter()
+                .map(|entry| (entry.entry_id, entry.score))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Decimal::from_ratio(1400u128, 1u128)),
+                (1, Decimal::from_ratio(1300u128, 1u128)),
+            ]
         );
 
-        let res: Vec<EntriesResponse> = app
+        let res: Vec<LeaderboardResponse> = app
             .wrap()
             .query_wasm_smart(
                 contract_addr.clone(),
-                &QueryMsg::Entries {
-                    category: "category_1".to_string(),
-                    start_after: Some(1),
-                    limit: Some(1),
+                &QueryMsg::CriterionLeaderboard {
+                    event: EVENT,
+                    category: None,
+                    criterion: "taste".to_string(),
+                    limit: None,
                 },
             )
             .unwrap();
-        assert_eq!(res.len(), 1);
         assert_eq!(
-            res[0].data,
-            Entry {
-                name: "entry_name".to_string(),
-                category: "category_1".to_string(),
-                maker_addr: Addr::unchecked(SECOND_MAKER.to_string()),
-                maker_name: "maker_name".to_string(),
-                breeder: "entry_breeder".to_string(),
-                genetics: "entry_genetics".to_string(),
-                farmer: "entry_farmer".to_string(),
-            }
-        )
+            res.iter()
+                .map(|entry| (entry.entry_id, entry.score))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Decimal::from_ratio(700u128, 1u128)),
+                (1, Decimal::from_ratio(650u128, 1u128)),
+            ]
+        );
+
+        // Amended and retracted ballots leave the trimmed scores, the outlier of the fifth
+        // maker is replaced and the ballots left are trimmed again
+        setup_vote(
+            &mut app,
+            contract_addr.clone(),
+            "fifth_maker",
+            "category_1".to_string(),
+            1,
+            votes(650, 650),
+        );
+        for (retracting, score) in [
+            (None, 1250u128),
+            (Some(SECOND_MAKER), 1300),
+            (Some("third_maker"), 1350),
+        ] {
+            if let Some(voter) = retracting {
+                app.execute_contract(
+                    Addr::unchecked(voter),
+                    contract_addr.clone(),
+                    &ExecuteMsg::RetractVote {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                    &[],
+                )
+                .unwrap();
+            };
+            let res: EntryScoresResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::EntryScores {
+                        event: EVENT,
+                        entry_id: 1,
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.score, Decimal::from_ratio(score, 1u128));
+        }
     }
 
     #[test]
-    fn test_votes() {
+    fn test_tie_breakers() {
         let mut app = mock_app();
         let admin_cw4_group = setup_cw4_group(&mut app, vec![ADMIN]);
         let makers_cw4_group = setup_cw4_group(
@@ -712,111 +6032,121 @@ mod query {
             makers_cw4_group.to_string(),
         );
 
-        setup_category(&mut app, contract_addr.clone(), "category_1".to_string());
-        setup_entry(
-            &mut app,
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
             contract_addr.clone(),
-            "entry_name".to_string(),
-            "category_1".to_string(),
-            FIRST_MAKER.to_string(),
-            "maker_name".to_string(),
-            "entry_breeder".to_string(),
-            "entry_genetics".to_string(),
-            "entry_farmer".to_string(),
+            &ExecuteMsg::AddCategory {
+                event: EVENT,
+                category: "category_1".to_string(),
+                criteria: None,
+                weights: None,
+                min_ballots: None,
+                scoring: None,
+                tie_breakers: Some(vec!["taste".to_string(), "post_melt".to_string()]),
+            },
+            &[],
+        )
+        .unwrap();
+        // Entry 4 is submitted a block after the others
+        for (name, maker) in [
+            ("entry_1", FIRST_MAKER),
+            ("entry_2", SECOND_MAKER),
+            ("entry_3", "third_maker"),
+            ("entry_4", "fourth_maker"),
+        ] {
+            if name == "entry_4" {
+                app.update_block(|block| block.height += 1);
+            };
+            setup_entry(
+                &mut app,
+                contract_addr.clone(),
+                name.to_string(),
+                "category_1".to_string(),
+                maker.to_string(),
+                "maker_name".to_string(),
+                "entry_breeder".to_string(),
+                "entry_genetics".to_string(),
+                "entry_farmer".to_string(),
+            );
+        }
+        setup_phase(&mut app, contract_addr.clone(), Phase::Voting);
+
+        // Every entry averages 2000, entry 1 wins on taste and entry 2 on post melt.
+        // Entries 3 and 4 are level on every criterion, which leaves entry 3 ahead
+        // on its earlier submission
+        for (entry_id, votes) in [
+            (1, default_votes(400, 400, 700, 500)),
+            (2, default_votes(400, 400, 600, 600)),
+            (3, default_votes(500, 500, 600, 400)),
+            (4, default_votes(500, 500, 600, 400)),
+        ] {
+            setup_vote(
+                &mut app,
+                contract_addr.clone(),
+                "fifth_maker",
+                "category_1".to_string(),
+                entry_id,
+                votes,
+            );
+        }
+
+        let leaderboard = |app: &App| -> Vec<(u64, u64, bool)> {
+            let res: Vec<LeaderboardResponse> = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::Leaderboard {
+                        event: EVENT,
+                        category: "category_1".to_string(),
+                        rank_by: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            res.iter()
+                .map(|entry| (entry.rank, entry.entry_id, entry.tied))
+                .collect()
+        };
+        assert_eq!(
+            leaderboard(&app),
+            vec![(1, 1, false), (2, 2, false), (3, 3, false), (4, 4, false)]
         );
+
+        // A second ballot on entry 4 keeps its average but wins on ballots
         setup_vote(
             &mut app,
             contract_addr.clone(),
             SECOND_MAKER,
             "category_1".to_string(),
-            1,
-            Votes {
-                look: Uint128::new(775),
-                smell: Uint128::new(820),
-                taste: Uint128::new(1000),
-                post_melt: Uint128::new(250),
-            },
+            4,
+            default_votes(500, 500, 600, 400),
         );
-        setup_vote(
-            &mut app,
-            contract_addr.clone(),
-            "third_maker",
-            "category_1".to_string(),
-            1,
-            Votes {
-                look: Uint128::new(450),
-                smell: Uint128::new(259),
-                taste: Uint128::new(720),
-                post_melt: Uint128::new(180),
-            },
+        assert_eq!(
+            leaderboard(&app),
+            vec![(1, 1, false), (2, 2, false), (3, 4, false), (4, 3, false)]
         );
+
+        // Entries 1 and 2 end up level on every criterion and ballot count and were
+        // submitted in the same block, so nothing breaks their tie
         setup_vote(
             &mut app,
             contract_addr.clone(),
-            "fourth_maker",
+            SECOND_MAKER,
             "category_1".to_string(),
             1,
-            Votes {
-                look: Uint128::new(603),
-                smell: Uint128::new(278),
-                taste: Uint128::new(383),
-                post_melt: Uint128::new(286),
-            },
+            default_votes(400, 400, 500, 700),
         );
         setup_vote(
             &mut app,
             contract_addr.clone(),
-            "fifth_maker",
+            FIRST_MAKER,
             "category_1".to_string(),
-            1,
-            Votes {
-                look: Uint128::new(950),
-                smell: Uint128::new(279),
-                taste: Uint128::new(632),
-                post_melt: Uint128::new(492),
-            },
+            2,
+            default_votes(400, 400, 600, 600),
+        );
+        assert_eq!(
+            leaderboard(&app),
+            vec![(1, 1, true), (1, 2, true), (3, 4, false), (4, 3, false)]
         );
-
-        let res: TallyVotesResponse = app
-            .wrap()
-            .query_wasm_smart(
-                contract_addr.clone(),
-                &QueryMsg::TallyVotes {
-                    entry_id: 1,
-                    start_after: None,
-                    limit: None,
-                },
-            )
-            .unwrap();
-        assert_eq!(res.votes.len(), 4);
-        assert_eq!(res.votes[0].maker_addr, "fifth_maker");
-        assert_eq!(res.votes[0].sum, Uint128::new(2353));
-        assert_eq!(res.votes[1].maker_addr, "fourth_maker");
-        assert_eq!(res.votes[1].sum, Uint128::new(1550));
-        assert_eq!(res.sum.look, Uint128::new(2778));
-        assert_eq!(res.sum.smell, Uint128::new(1636));
-        assert_eq!(res.sum.taste, Uint128::new(2735));
-        assert_eq!(res.sum.post_melt, Uint128::new(1208));
-
-        let res: TallyVotesResponse = app
-            .wrap()
-            .query_wasm_smart(
-                contract_addr.clone(),
-                &QueryMsg::TallyVotes {
-                    entry_id: 1,
-                    start_after: Some("fourth_maker".to_string()),
-                    limit: Some(2),
-                },
-            )
-            .unwrap();
-        assert_eq!(res.votes.len(), 2);
-        assert_eq!(res.votes[0].maker_addr, SECOND_MAKER);
-        assert_eq!(res.votes[0].sum, Uint128::new(2845));
-        assert_eq!(res.votes[1].maker_addr, "third_maker");
-        assert_eq!(res.votes[1].sum, Uint128::new(1609));
-        assert_eq!(res.sum.look, Uint128::new(1225));
-        assert_eq!(res.sum.smell, Uint128::new(1079));
-        assert_eq!(res.sum.taste, Uint128::new(1720));
-        assert_eq!(res.sum.post_melt, Uint128::new(430));
     }
 }